@@ -0,0 +1,92 @@
+//! Compares decoding JSON payloads with `serde_json` and with the `simd-json` backend.
+//!
+//! Run with `cargo bench --bench json --features simd-json`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use greengrass_sdk::protocol::{Headers, Message, MessageFlags, MessageType, OwnedMessage};
+use serde::{Deserialize, Serialize};
+
+/// A typical telemetry message published over local pub/sub.
+#[derive(Debug, Serialize, Deserialize)]
+struct Telemetry {
+    device_id: String,
+    timestamp: u64,
+    firmware: String,
+    tags: Vec<String>,
+    readings: Vec<Reading>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Reading {
+    sensor: String,
+    unit: String,
+    value: f64,
+    ok: bool,
+}
+
+fn telemetry(readings: usize) -> Telemetry {
+    Telemetry {
+        device_id: "charger-0042".into(),
+        timestamp: 1_700_000_000_123,
+        firmware: "2.14.0-rc.1".into(),
+        tags: vec!["site:berlin".into(), "rack:7".into(), "phase:\"L1\"".into()],
+        readings: (0..readings)
+            .map(|i| Reading {
+                sensor: format!("sensor-{i}"),
+                unit: "kWh".into(),
+                value: i as f64 * 0.37,
+                ok: i % 7 != 0,
+            })
+            .collect(),
+    }
+}
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    // Warm up, then report the fastest of a few rounds, which is the least disturbed by noise.
+    for _ in 0..1_000 {
+        f();
+    }
+    let fastest = (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            let mut iterations = 0u32;
+            while start.elapsed() < Duration::from_millis(500) {
+                f();
+                iterations += 1;
+            }
+            start.elapsed().as_nanos() as f64 / iterations as f64
+        })
+        .fold(f64::INFINITY, f64::min);
+    println!("{name:<42} {fastest:>10.0} ns/iter");
+}
+
+fn main() {
+    for readings in [1, 16, 256] {
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let bytes = Message::new(headers, Some(telemetry(readings))).to_bytes().unwrap();
+        let message = OwnedMessage::from_bytes(Bytes::from(bytes)).unwrap();
+        let payload = message.payload().clone();
+        println!("{readings} reading(s), {} byte payload:", payload.len());
+
+        bench("  serde_json::from_slice", || {
+            black_box(serde_json::from_slice::<Telemetry>(black_box(&payload)).unwrap());
+        });
+        let mut copy = Vec::new();
+        bench("  simd_json::serde::from_slice (copied)", || {
+            copy.clear();
+            copy.extend_from_slice(black_box(&payload));
+            black_box(simd_json::serde::from_slice::<Telemetry>(&mut copy).unwrap());
+        });
+        bench("  OwnedMessage::message (serde_json)", || {
+            black_box(black_box(&message).message::<Telemetry>().unwrap());
+        });
+        bench("  OwnedMessage::message_owned (simd-json)", || {
+            black_box(black_box(&message).message_owned::<Telemetry>().unwrap());
+        });
+    }
+}
@@ -0,0 +1,460 @@
+use core::{fmt, future::Future, pin::Pin};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    time::Instant,
+};
+
+use crate::{
+    connection::{Connection, Subscription},
+    protocol::{ComponentUpdateSubscriptionResponse, PreComponentUpdateEvent, RecheckAfterMs},
+    sync::lock,
+    DeferralPolicy, DeploymentRecord, DeploymentStage, DeploymentTracker, Error, Result,
+};
+
+use tokio::{
+    sync::{broadcast, watch},
+    task::JoinSet,
+    time::timeout,
+};
+use tracing::{debug, error, trace, warn};
+use uuid::Uuid;
+
+/// A cloneable handle for pausing component updates.
+///
+/// Each call to [`UpdatePauser::pause`] returns an [`UpdatePauseGuard`] and component updates stay
+/// deferred for as long as at least one guard (from any clone of the handle) is alive. This allows
+/// independent tasks to each protect their own critical section without coordinating with each
+/// other.
+#[derive(Debug, Clone)]
+pub struct UpdatePauser {
+    shared: Arc<Shared>,
+}
+
+impl UpdatePauser {
+    pub(crate) fn new(conn: Connection) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                conn,
+                start_lock: tokio::sync::Mutex::new(()),
+                state: Mutex::new(State {
+                    guards: 0,
+                    hooks: Vec::new(),
+                    trackers: 0,
+                    policy: DeferralPolicy::default(),
+                    task: None,
+                    // Closed until the task is started.
+                    records: broadcast::channel(1).1,
+                }),
+            }),
+        }
+    }
+
+    /// Pause component updates.
+    ///
+    /// The updates remain paused until the returned guard and all other guards obtained from this
+    /// handle (or its clones) are dropped.
+    pub async fn pause(&self) -> Result<UpdatePauseGuard> {
+        self.with_task(|state| {
+            state.guards += 1;
+            if let Some(task) = &state.task {
+                task.send_replace(true);
+            }
+        })
+        .await?;
+
+        Ok(UpdatePauseGuard { pauser: self.clone() })
+    }
+
+    /// Whether component updates are currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.shared.state().guards > 0
+    }
+
+    /// Set the policy for deferring updates while they're paused.
+    ///
+    /// The policy is shared by all clones of this handle and applies from the next update event
+    /// onwards, even if the updates are already paused.
+    pub fn set_deferral_policy(&self, policy: DeferralPolicy) {
+        self.shared.state().policy = policy;
+    }
+
+    /// The policy for deferring updates while they're paused.
+    pub fn deferral_policy(&self) -> DeferralPolicy {
+        self.shared.state().policy.clone()
+    }
+
+    pub(crate) async fn add_pre_update_hook(&self, hook: PreUpdateHook) -> Result<()> {
+        self.with_task(|state| state.hooks.push(hook)).await
+    }
+
+    pub(crate) async fn track_deployments(&self) -> Result<DeploymentTracker> {
+        let receiver = self
+            .with_task(|state| {
+                state.trackers += 1;
+
+                state.records.resubscribe()
+            })
+            .await?;
+
+        Ok(DeploymentTracker::new(receiver, self.clone()))
+    }
+
+    pub(crate) fn remove_tracker(&self) {
+        let mut state = self.shared.state();
+        state.trackers -= 1;
+        state.stop_task_if_unneeded();
+    }
+
+    /// Run `f` on the state, first starting the task handling the updates if it's not running.
+    async fn with_task<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut State) -> T,
+    {
+        // Ensures only one task at a time sets up the subscription.
+        let _start_lock = self.shared.start_lock.lock().await;
+
+        {
+            let mut state = self.shared.state();
+            if state.running_task().is_some() {
+                return Ok(f(&mut state));
+            }
+        }
+
+        let component_updates = ComponentUpdates::new(self.shared.conn.clone()).await?;
+        let mut state = self.shared.state();
+        // The task may be restarting after an error, with guards still alive.
+        let (paused_tx, paused_rx) = watch::channel(state.guards > 0);
+        let (records_tx, records_rx) = broadcast::channel(RECORDS_CAPACITY);
+        tokio::spawn(component_updates.handle_updates(
+            Arc::downgrade(&self.shared),
+            paused_rx,
+            records_tx,
+        ));
+        state.task = Some(paused_tx);
+        state.records = records_rx;
+
+        Ok(f(&mut state))
+    }
+}
+
+/// A guard that keeps component updates paused while it is alive.
+///
+/// Obtained through [`UpdatePauser::pause`].
+#[derive(Debug)]
+#[must_use = "component updates are resumed as soon as the guard is dropped"]
+pub struct UpdatePauseGuard {
+    pauser: UpdatePauser,
+}
+
+impl Drop for UpdatePauseGuard {
+    fn drop(&mut self) {
+        let mut state = self.pauser.shared.state();
+        state.guards -= 1;
+        if state.guards > 0 {
+            return;
+        }
+
+        // Either way, the task releases any outstanding deferral.
+        if let Some(task) = &state.task {
+            task.send_replace(false);
+        }
+        state.stop_task_if_unneeded();
+    }
+}
+
+/// A hook run on each `PreComponentUpdateEvent`.
+pub(crate) type PreUpdateHook =
+    Arc<dyn Fn(PreComponentUpdateEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+#[derive(Debug)]
+struct Shared {
+    conn: Connection,
+    start_lock: tokio::sync::Mutex<()>,
+    state: Mutex<State>,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+}
+
+struct State {
+    guards: usize,
+    hooks: Vec<PreUpdateHook>,
+    trackers: usize,
+    policy: DeferralPolicy,
+    // Tells the task handling the updates if they're paused. Dropping it stops the task.
+    task: Option<watch::Sender<bool>>,
+    // Resubscribed to for each new tracker. Only the task holds the sender, so the trackers see
+    // the end of the records when it exits.
+    records: broadcast::Receiver<DeploymentRecord>,
+}
+
+impl State {
+    fn running_task(&self) -> Option<&watch::Sender<bool>> {
+        // The task could have exited on its own because of a connection error.
+        self.task.as_ref().filter(|task| !task.is_closed())
+    }
+
+    fn stop_task_if_unneeded(&mut self) {
+        if self.guards == 0 && self.hooks.is_empty() && self.trackers == 0 {
+            self.task.take();
+        }
+    }
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("State")
+            .field("guards", &self.guards)
+            .field("hooks", &self.hooks.len())
+            .field("trackers", &self.trackers)
+            .field("policy", &self.policy)
+            .field("task", &self.task)
+            .field("records", &self.records)
+            .finish()
+    }
+}
+
+struct ComponentUpdates {
+    conn: Connection,
+    subscription: Subscription<ComponentUpdateSubscriptionResponse>,
+}
+
+impl ComponentUpdates {
+    async fn new(conn: Connection) -> Result<Self> {
+        let subscription = conn.subscribe_to_component_updates().await?;
+
+        Ok(Self { conn, subscription })
+    }
+
+    async fn handle_updates(
+        mut self,
+        shared: Weak<Shared>,
+        mut paused: watch::Receiver<bool>,
+        records: broadcast::Sender<DeploymentRecord>,
+    ) {
+        let record = |deployment_id, stage| {
+            // No receivers isn't an error, it just means nobody is tracking deployments.
+            let _ = records.send(DeploymentRecord::new(deployment_id, stage));
+        };
+        // The deployment we last deferred and that hasn't completed yet.
+        let mut deferred_deployment = None;
+        // The deployments we've seen a pre-update event for but no post-update event yet, at most
+        // the one in progress.
+        let mut deployments = HashMap::new();
+
+        loop {
+            trace!("Waiting for the next component update event..");
+            let res = tokio::select! {
+                res = self.subscription.next() => match res {
+                    Some(res) => res,
+                    None => {
+                        warn!("Component update subscription ended by the server");
+
+                        break;
+                    }
+                },
+                res = paused.changed() => {
+                    if res.is_err() {
+                        break;
+                    }
+                    if !*paused.borrow_and_update() {
+                        if let Some(deployment_id) = deferred_deployment.take() {
+                            self.release(deployment_id).await;
+                            record(deployment_id, DeploymentStage::Proceeded);
+                        }
+                    }
+
+                    continue;
+                }
+            };
+            let update = match res {
+                Ok(update) => update,
+                Err(e @ Error::Io(_))
+                | Err(e @ Error::InternalServer(_))
+                | Err(e @ Error::Protocol(_))
+                | Err(e @ Error::ConnectionClosed)
+                | Err(e @ Error::ConnectionDead) => {
+                    error!("{e}");
+
+                    break;
+                }
+                Err(e) => {
+                    warn!("{e}");
+
+                    continue;
+                }
+            };
+            trace!("Received component update: {update:?}");
+
+            let response = match update.payload() {
+                Some(p) => p,
+                None => {
+                    warn!("Received component update without (expected) payload");
+
+                    continue;
+                }
+            };
+            if let Some(post_update_event) = response.post_update_event() {
+                let deployment_id = post_update_event.deployment_id();
+                deployments.remove(&deployment_id);
+                if deferred_deployment == Some(deployment_id) {
+                    deferred_deployment = None;
+                }
+                record(deployment_id, DeploymentStage::Completed);
+            }
+            let pre_update_event = match response.pre_update_event() {
+                Some(e) => e.clone(),
+                None => {
+                    debug!("No `preUpdateEvent` in the update, ignoring...");
+
+                    continue;
+                }
+            };
+            let deployment_id = pre_update_event.deployment_id();
+            // The nucleus handles one deployment at a time, so one we haven't seen before means
+            // the others were cancelled or superseded and won't get a post-update event.
+            if !deployments.contains_key(&deployment_id) {
+                deployments.clear();
+                if deferred_deployment.is_some_and(|id| id != deployment_id) {
+                    deferred_deployment = None;
+                }
+            }
+            let deployment = deployments.entry(deployment_id).or_insert_with(|| {
+                record(
+                    deployment_id,
+                    DeploymentStage::Announced {
+                        is_ggc_restarting: pre_update_event.is_ggc_restarting(),
+                    },
+                );
+
+                Deployment { first_seen: Instant::now(), hooks_run: false, deferrals: 0 }
+            });
+            let Some((policy, hooks)) = shared.upgrade().map(|shared| {
+                let state = shared.state();
+                (state.policy.clone(), state.hooks.clone())
+            }) else {
+                break;
+            };
+
+            if !deployment.hooks_run {
+                // Hold the deployment back while the hooks run, then decide as usual below.
+                if !hooks.is_empty() {
+                    match self
+                        .conn
+                        .defer_component_update(
+                            deployment_id,
+                            policy.message(),
+                            policy.hook_deferral(),
+                        )
+                        .await
+                    {
+                        Ok(_) => {
+                            deployment.deferrals += 1;
+                            record(
+                                deployment_id,
+                                DeploymentStage::Deferred { count: deployment.deferrals },
+                            );
+                            deferred_deployment = Some(deployment_id);
+                        }
+                        Err(e) => error!("Error deferring component update: {:?}", e),
+                    }
+                }
+                run_hooks(hooks, &pre_update_event, &policy).await;
+                deployment.hooks_run = true;
+            }
+
+            let recheck_after_ms = if *paused.borrow() {
+                policy.recheck_after(
+                    deployment.first_seen.elapsed(),
+                    pre_update_event.is_ggc_restarting(),
+                )
+            } else {
+                RecheckAfterMs::DontDefer
+            };
+            if recheck_after_ms == RecheckAfterMs::DontDefer {
+                debug!("Not deferring deployment {deployment_id}");
+            }
+
+            if let Err(e) = self
+                .conn
+                .defer_component_update(deployment_id, policy.message(), recheck_after_ms)
+                .await
+            {
+                error!("Error deferring component update: {:?}", e);
+
+                continue;
+            }
+            deferred_deployment = match recheck_after_ms {
+                RecheckAfterMs::Defer(_) => {
+                    deployment.deferrals += 1;
+                    record(
+                        deployment_id,
+                        DeploymentStage::Deferred { count: deployment.deferrals },
+                    );
+
+                    Some(deployment_id)
+                }
+                RecheckAfterMs::DontDefer => {
+                    record(deployment_id, DeploymentStage::Proceeded);
+
+                    None
+                }
+            };
+        }
+
+        if let Some(deployment_id) = deferred_deployment {
+            self.release(deployment_id).await;
+            record(deployment_id, DeploymentStage::Proceeded);
+        }
+    }
+
+    /// Tell the nucleus to not wait for the next recheck before proceeding with the deployment.
+    async fn release(&self, deployment_id: Uuid) {
+        debug!("Releasing deferred deployment {deployment_id}");
+        if let Err(e) =
+            self.conn.defer_component_update(deployment_id, None, RecheckAfterMs::DontDefer).await
+        {
+            warn!("Error releasing deferred component update: {e}");
+        }
+    }
+}
+
+struct Deployment {
+    first_seen: Instant,
+    hooks_run: bool,
+    deferrals: u32,
+}
+
+/// Run all hooks concurrently, waiting for them to finish or for the policy's hook timeout.
+async fn run_hooks(
+    hooks: Vec<PreUpdateHook>,
+    pre_update_event: &PreComponentUpdateEvent,
+    policy: &DeferralPolicy,
+) {
+    if hooks.is_empty() {
+        return;
+    }
+
+    debug!("Running {} pre-update hook(s)", hooks.len());
+    let mut set = JoinSet::new();
+    for hook in hooks {
+        set.spawn(hook(pre_update_event.clone()));
+    }
+    let all_done = async {
+        while let Some(res) = set.join_next().await {
+            if let Err(e) = res {
+                warn!("Pre-update hook failed: {e}");
+            }
+        }
+    };
+    if timeout(policy.hook_timeout(), all_done).await.is_err() {
+        warn!("Pre-update hooks did not finish in time, not waiting for them any longer");
+        set.detach_all();
+    }
+}
+
+const RECORDS_CAPACITY: usize = 64;
@@ -0,0 +1,52 @@
+use core::time::Duration;
+
+/// Settings for the periodic pings sent to the server to detect a dead connection.
+///
+/// See [`super::Connection::set_keep_alive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAlive {
+    interval: Duration,
+    max_missed_pongs: u32,
+}
+
+impl KeepAlive {
+    /// Create new keep-alive settings.
+    ///
+    /// A ping is sent every `interval` and the connection is considered dead once
+    /// `max_missed_pongs` pings in a row have not been answered by the time the next one is due. A
+    /// `max_missed_pongs` of `0` is treated as `1`, and an `interval` shorter than 1 millisecond as
+    /// 1 millisecond.
+    pub fn new(interval: Duration, max_missed_pongs: u32) -> Self {
+        Self { interval: interval.max(MIN_INTERVAL), max_missed_pongs: max_missed_pongs.max(1) }
+    }
+
+    /// The interval between pings.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// The number of unanswered pings after which the connection is considered dead.
+    pub fn max_missed_pongs(&self) -> u32 {
+        self.max_missed_pongs
+    }
+}
+
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::KeepAlive;
+
+    #[test]
+    fn clamped_settings() {
+        let keep_alive = KeepAlive::new(Duration::ZERO, 0);
+        assert_eq!(keep_alive.interval(), Duration::from_millis(1));
+        assert_eq!(keep_alive.max_missed_pongs(), 1);
+
+        let keep_alive = KeepAlive::new(Duration::from_secs(5), 3);
+        assert_eq!(keep_alive.interval(), Duration::from_secs(5));
+        assert_eq!(keep_alive.max_missed_pongs(), 3);
+    }
+}
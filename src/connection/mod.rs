@@ -0,0 +1,461 @@
+use core::{
+    fmt::Debug,
+    sync::atomic::{AtomicU32, Ordering},
+};
+use std::sync::{Arc, Weak};
+
+use serde::de::IgnoredAny;
+use tokio::{
+    net::{unix::OwnedReadHalf, UnixStream},
+    sync::Mutex,
+    task::JoinHandle,
+    time::{interval, MissedTickBehavior},
+};
+use tracing::{debug, error, trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    env,
+    protocol::{
+        prelude::{Prelude, SIZE},
+        ComponentUpdateSubscriptionRequest, ComponentUpdateSubscriptionResponse, ConnectRequest,
+        ConnectResponse, DecodePayload, Decoded, DeferComponentUpdate, DeferComponentUpdateRequest,
+        EncodePayload, Headers, Message, MessageFlags, MessageType, Operation, OwnedMessage,
+        RecheckAfterMs, StreamingOperation, SubscribeToComponentUpdates, UpdateState,
+        UpdateStateRequest,
+    },
+    sync::lock,
+    Error, Result,
+};
+
+mod keep_alive;
+pub use keep_alive::KeepAlive;
+mod reader;
+use reader::FrameReader;
+mod streams;
+use streams::{CloseReason, Streams};
+mod subscription;
+pub use subscription::Subscription;
+mod writer;
+use writer::{write_message, Writer};
+
+/// A connection to the server.
+///
+/// This is the low-level API to directly interact with the server. Typically you would use the
+/// [`crate::IpcClient`] instead.
+///
+/// A background task reads all incoming messages and routes each of them to the call or
+/// subscription waiting for it, based on its stream ID. Clones of the connection share the same
+/// socket.
+///
+/// Pings from the server are answered automatically. See [`Connection::set_keep_alive`] for
+/// pinging the server in turn, to detect a dead connection.
+///
+/// # Cancel safety
+///
+/// All methods are cancel safe, so calls can be wrapped in a `timeout` or raced in
+/// `tokio::select!`. A message only partially written when the calling future is dropped is
+/// completed before the next one, and the background task buffers partially received messages, so
+/// the stream never gets out of sync. The response to a cancelled call is discarded once it
+/// arrives, and a subscription cancelled before it's returned has its stream terminated.
+#[derive(Debug, Clone)]
+pub struct Connection {
+    inner: Arc<Inner>,
+}
+
+impl Connection {
+    /// Creates a new connection to the server.
+    pub async fn new(socket_path: &'static str, auth_token: &'static str) -> Result<Self> {
+        let (reader, writer) = UnixStream::connect(&socket_path).await?.into_split();
+        let mut reader = FrameReader::new(reader);
+        let writer = Arc::new(Mutex::new(Writer::new(writer)));
+
+        // Handshake
+        write_message(&writer, ConnectRequest::new(auth_token)?).await?;
+        let frame = reader.read_frame().await?;
+        let response = Message::<ConnectResponse>::from_bytes(&mut &frame[..])?;
+        trace!("Received message: {response:?}");
+        let headers = response.headers();
+        if headers.message_type() != MessageType::ConnectAck {
+            return Err(Error::Protocol("Invalid connection response".into()));
+        }
+        if !headers.message_flags().contains(MessageFlags::ConnectionAccepted) {
+            return Err(Error::ConnectionRefused);
+        }
+
+        let streams = Streams::new();
+        let unanswered_pings = Arc::new(AtomicU32::new(0));
+        let reader = tokio::spawn(read_messages(
+            reader,
+            writer.clone(),
+            streams.clone(),
+            unanswered_pings.clone(),
+        ));
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                writer,
+                streams,
+                unanswered_pings,
+                reader,
+                keep_alive: std::sync::Mutex::new(None),
+                socket_path,
+                auth_token,
+            }),
+        })
+    }
+
+    /// Creates a new connection to the server, fetching the socket path and auth token from the
+    /// environment.
+    pub async fn from_env() -> Result<Self> {
+        let socket_path = env::socket_path()?;
+        let auth_token = env::auth_token()?;
+
+        Self::new(socket_path, auth_token).await
+    }
+
+    /// Subscribes to component updates.
+    ///
+    /// If you subscribe to the component updates, nucleus will expect you to respond to all
+    /// `pre-update` events using `defer_component_update` method. If you do not want to defer the
+    /// update, pass `RecheckAfterMs::DontDefer` as the `recheck_after_ms` argument.
+    ///
+    /// All of this is handled for you by the high-level API provided by [`crate::IpcClient`].
+    pub async fn subscribe_to_component_updates(
+        &self,
+    ) -> Result<Subscription<ComponentUpdateSubscriptionResponse>> {
+        self.subscribe::<SubscribeToComponentUpdates>(ComponentUpdateSubscriptionRequest {}).await
+    }
+
+    /// Defers a component update.
+    pub async fn defer_component_update(
+        &self,
+        deployment_id: Uuid,
+        message: Option<&str>,
+        recheck_after_ms: RecheckAfterMs,
+    ) -> Result<()> {
+        let request =
+            DeferComponentUpdateRequest::new_payload(deployment_id, message, recheck_after_ms);
+        let _ = self.invoke::<DeferComponentUpdate>(request).await?;
+
+        Ok(())
+    }
+
+    /// Updates the state of the lifecycle.
+    pub async fn update_state(&self, state: crate::LifecycleState) -> Result<()> {
+        let _ = self.invoke::<UpdateState>(UpdateStateRequest::new_payload(state)).await?;
+
+        Ok(())
+    }
+
+    /// Invokes the operation `Op` and returns its response.
+    ///
+    /// A response without a payload is decoded from an empty JSON object.
+    pub async fn invoke<Op>(&self, request: Op::Request<'_>) -> Result<Op::Response>
+    where
+        Op: Operation,
+    {
+        let id = self.next_stream_id()?;
+        let response = self.call::<_, Op::Response>(Message::operation::<Op>(id, request)).await?;
+
+        match response.into_payload() {
+            Some(payload) => Ok(payload),
+            None => <Op::Response as DecodePayload<'_>>::decode(b"{}")
+                .map_err(|_| Error::Protocol("Missing payload in the response".into())),
+        }
+    }
+
+    /// Subscribes to the events of the streaming operation `Op`.
+    ///
+    /// The initial response of the server is checked for errors and then discarded. The events
+    /// that follow are received through the returned subscription.
+    pub async fn subscribe<Op>(&self, request: Op::Request<'_>) -> Result<Subscription<Op::Event>>
+    where
+        Op: StreamingOperation,
+    {
+        let id = self.next_stream_id()?;
+
+        self.subscribe_message::<_, Op::Response, _>(Message::operation::<Op>(id, request)).await
+    }
+
+    /// Calls a method on a service.
+    ///
+    /// Returns the response, which is expected to be the only message on the stream of the
+    /// request.
+    pub async fn call<RequestPayload, ResponsePayload>(
+        &self,
+        request: Message<'_, RequestPayload>,
+    ) -> Result<Message<'static, ResponsePayload>>
+    where
+        RequestPayload: EncodePayload + Debug,
+        ResponsePayload: for<'de> DecodePayload<'de> + Debug,
+    {
+        let stream_id = request.headers().stream_id();
+        let mut route = self.inner.streams.register(stream_id)?;
+        self.send_message(request).await?;
+
+        trace!("Waiting for response with stream ID {stream_id}");
+        let message = route.recv().await.ok_or(Error::ConnectionClosed)??;
+
+        parse_response(&message, true)
+    }
+
+    /// Subscribes to a stream of events, sending the given request message.
+    ///
+    /// The initial response of the server is checked for errors and then discarded. The events
+    /// that follow on the stream of the request are received through the returned subscription.
+    pub async fn subscribe_message<RequestPayload, ResponsePayload, Event>(
+        &self,
+        request: Message<'_, RequestPayload>,
+    ) -> Result<Subscription<Event>>
+    where
+        RequestPayload: EncodePayload + Debug,
+        ResponsePayload: for<'de> DecodePayload<'de> + Debug,
+        Event: for<'de> DecodePayload<'de> + Debug,
+    {
+        let stream_id = request.headers().stream_id();
+        let route = self.inner.streams.register(stream_id)?;
+        // Set up first, so the stream is terminated if this future is dropped before it returns.
+        let mut subscription = Subscription::new(route, Arc::downgrade(&self.inner));
+        if let Err(e) = self.send_message(request).await {
+            // Either nothing was sent or the connection is broken.
+            subscription.mark_ended();
+
+            return Err(e);
+        }
+
+        trace!("Waiting for subscription response with stream ID {stream_id}");
+        subscription.accept::<ResponsePayload>().await?;
+
+        Ok(subscription)
+    }
+
+    /// Sends a message.
+    pub async fn send_message<Payload>(&self, message: Message<'_, Payload>) -> Result<()>
+    where
+        Payload: EncodePayload + Debug,
+    {
+        write_message(&self.inner.writer, message).await
+    }
+
+    /// Set up periodic pings to the server, or stop them with `None`.
+    ///
+    /// Once the server fails to answer [`KeepAlive::max_missed_pongs`] pings in a row, the
+    /// connection is considered dead: all pending calls and subscriptions, as well as any new ones,
+    /// fail with [`Error::ConnectionDead`]. Pings are disabled by default.
+    pub fn set_keep_alive(&self, keep_alive: Option<KeepAlive>) {
+        let task = keep_alive
+            .map(|keep_alive| tokio::spawn(send_pings(Arc::downgrade(&self.inner), keep_alive)));
+        let previous = core::mem::replace(&mut *self.inner.keep_alive(), task);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+        self.inner.unanswered_pings.store(0, Ordering::SeqCst);
+    }
+
+    /// The path of the socket.
+    pub fn socket_path(&self) -> &'static str {
+        self.inner.socket_path
+    }
+
+    /// The authentication token.
+    pub fn auth_token(&self) -> &'static str {
+        self.inner.auth_token
+    }
+
+    pub(crate) fn next_stream_id(&self) -> Result<i32> {
+        self.inner.streams.next_stream_id()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Inner {
+    writer: Arc<Mutex<Writer>>,
+    streams: Streams,
+    // The number of pings sent since the last pong was received.
+    unanswered_pings: Arc<AtomicU32>,
+    reader: JoinHandle<()>,
+    keep_alive: std::sync::Mutex<Option<JoinHandle<()>>>,
+    socket_path: &'static str,
+    auth_token: &'static str,
+}
+
+impl Inner {
+    fn keep_alive(&self) -> std::sync::MutexGuard<'_, Option<JoinHandle<()>>> {
+        lock(&self.keep_alive)
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.reader.abort();
+        if let Some(keep_alive) = self.keep_alive().take() {
+            keep_alive.abort();
+        }
+    }
+}
+
+/// Tell the server that the client is done with the stream.
+async fn terminate_stream(inner: &Inner, stream_id: i32) -> Result<()> {
+    let headers =
+        Headers::new(stream_id, MessageType::Application, MessageFlags::TerminateStream.into());
+
+    write_message(&inner.writer, Message::<()>::new(headers, None)).await
+}
+
+/// Ping the server periodically until the connection is dropped or found to be dead.
+async fn send_pings(inner: Weak<Inner>, keep_alive: KeepAlive) {
+    let mut ticks = interval(keep_alive.interval());
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        let Some(inner) = inner.upgrade() else {
+            break;
+        };
+
+        let unanswered = inner.unanswered_pings.load(Ordering::SeqCst);
+        if unanswered >= keep_alive.max_missed_pongs() {
+            error!("No response to the last {unanswered} ping(s), considering the connection dead");
+            inner.reader.abort();
+            inner.streams.close(CloseReason::Dead);
+
+            break;
+        }
+
+        // Count the ping before sending it, so a quick pong can't be missed.
+        inner.unanswered_pings.fetch_add(1, Ordering::SeqCst);
+        let ping =
+            Message::<()>::new(Headers::new(0, MessageType::Ping, MessageFlags::none()), None);
+        if let Err(e) = write_message(&inner.writer, ping).await {
+            warn!("Error sending ping: {e}");
+        }
+    }
+}
+
+/// Parse a message received on the stream of a call or subscription.
+fn parse_response<Payload>(
+    message: &OwnedMessage,
+    last_response: bool,
+) -> Result<Message<'static, Payload>>
+where
+    Payload: for<'de> DecodePayload<'de> + Debug,
+{
+    let message = message.message_owned::<Payload>()?;
+    trace!("Received message: {message:?}");
+    let headers = message.headers();
+    let stream_terminated = headers.message_flags().contains(MessageFlags::TerminateStream);
+    // Should we return errors here? 🤔
+    if last_response && !stream_terminated {
+        warn!("Response unexpectedly not marked as end of stream");
+    } else if !last_response && stream_terminated {
+        warn!("Unexpected end of stream");
+    }
+
+    let message_type = headers.message_type();
+    if message_type != MessageType::Application {
+        // The message belongs to the stream we're interested in so the message type must match.
+        return Err(Error::UnexpectedMessageType {
+            expected: MessageType::Application,
+            received: message_type,
+        });
+    }
+
+    Ok(message)
+}
+
+/// Read all messages from the socket and route them to whoever is waiting for them.
+///
+/// Pings are answered right away and pongs reset the count of unanswered pings.
+async fn read_messages(
+    mut reader: FrameReader<OwnedReadHalf>,
+    writer: Arc<Mutex<Writer>>,
+    streams: Streams,
+    unanswered_pings: Arc<AtomicU32>,
+) {
+    loop {
+        let frame = match reader.read().await {
+            Ok(Decoded::Frame(frame)) => frame,
+            Ok(Decoded::TooLarge { total_len, headers_len, stream_id }) => {
+                warn!("Skipped message of {total_len} bytes, exceeding the size limits");
+                if let Some(stream_id) = stream_id {
+                    streams.fail(stream_id, Error::FrameTooLarge { total_len, headers_len });
+                }
+
+                continue;
+            }
+            Err(e) => {
+                match e {
+                    Error::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                        debug!("Connection closed by the server")
+                    }
+                    e => error!("Error reading from the connection: {e}"),
+                }
+
+                break;
+            }
+        };
+        let frame = frame.freeze();
+        let message = match OwnedMessage::from_bytes(frame.clone()) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Dropping malformed message: {e}");
+                // Let the caller know, if we can tell who's waiting for the message.
+                if let Ok(headers) = frame_headers(&frame) {
+                    streams.fail(headers.stream_id(), e);
+                }
+
+                continue;
+            }
+        };
+        let stream_id = message.stream_id();
+        match message.message_type() {
+            MessageType::Ping => {
+                trace!("Received ping, sending pong");
+                let pong = Message::<()>::new(
+                    Headers::new(stream_id, MessageType::Pong, MessageFlags::none()),
+                    None,
+                );
+                if let Err(e) = write_message(&writer, pong).await {
+                    warn!("Error sending pong: {e}");
+                }
+
+                continue;
+            }
+            MessageType::Pong => {
+                trace!("Received pong");
+                unanswered_pings.store(0, Ordering::SeqCst);
+
+                continue;
+            }
+            MessageType::ProtocolError | MessageType::InternalError if stream_id == 0 => {
+                // Not tied to any stream, so the error applies to the whole connection.
+                let reason = match message.message::<IgnoredAny>() {
+                    Err(Error::Protocol(e)) => CloseReason::ProtocolError(e),
+                    Err(Error::InternalServer(e)) => CloseReason::InternalError(e),
+                    _ => CloseReason::ProtocolError("Malformed error message".into()),
+                };
+                error!("Connection failed: {:?}", reason);
+                streams.close(reason);
+
+                break;
+            }
+            _ => (),
+        }
+        let last = message.message_flags().contains(MessageFlags::TerminateStream);
+        if !streams.dispatch(stream_id, message, last) {
+            trace!("No one waiting for messages with stream ID {stream_id}, dropping message");
+        }
+    }
+
+    streams.close(CloseReason::Closed);
+}
+
+/// Parse just the headers of a message, to find out where to route it.
+fn frame_headers(frame: &[u8]) -> Result<Headers<'_>> {
+    let prelude = Prelude::from_bytes(&mut &frame[..])?;
+    let mut headers_bytes = frame
+        .get(SIZE..SIZE + prelude.headers_len())
+        .ok_or(Error::Protocol("Invalid header length".into()))?;
+
+    Headers::from_bytes(&mut headers_bytes)
+}
@@ -0,0 +1,90 @@
+use bytes::BytesMut;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::{
+    protocol::{Decoded, MessageCodec},
+    Error, Result,
+};
+
+/// Reads messages from the socket, buffering the incoming bytes until a message is complete.
+#[derive(Debug)]
+pub(crate) struct FrameReader<R> {
+    reader: R,
+    buffer: BytesMut,
+    codec: MessageCodec,
+}
+
+impl<R> FrameReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    pub(crate) fn new(reader: R) -> Self {
+        Self { reader, buffer: BytesMut::with_capacity(BUFFER_SIZE), codec: MessageCodec::new() }
+    }
+
+    /// Read the next message, including those too large to be received.
+    ///
+    /// This method is cancel safe: the bytes of a partially received message stay buffered until
+    /// the next call.
+    pub(crate) async fn read(&mut self) -> Result<Decoded> {
+        loop {
+            if let Some(decoded) = self.codec.decode_frame(&mut self.buffer)? {
+                // Don't hold on to the memory reserved for an unusually large message.
+                if self.buffer.is_empty() && self.buffer.capacity() > MAX_RETAINED_CAPACITY {
+                    self.buffer = BytesMut::with_capacity(BUFFER_SIZE);
+                }
+
+                return Ok(decoded);
+            }
+            if self.reader.read_buf(&mut self.buffer).await? == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+        }
+    }
+
+    /// Read the next message, failing if it's too large.
+    ///
+    /// Cancel safe, like [`FrameReader::read`].
+    pub(crate) async fn read_frame(&mut self) -> Result<BytesMut> {
+        match self.read().await? {
+            Decoded::Frame(frame) => Ok(frame),
+            Decoded::TooLarge { total_len, headers_len, .. } => {
+                Err(Error::FrameTooLarge { total_len, headers_len })
+            }
+        }
+    }
+}
+
+const BUFFER_SIZE: usize = 8 * 1024;
+const MAX_RETAINED_CAPACITY: usize = 64 * 1024;
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use tokio::{io::AsyncWriteExt, time::timeout};
+
+    use super::FrameReader;
+    use crate::protocol::{Headers, Message, MessageFlags, MessageType};
+
+    #[tokio::test]
+    async fn cancelled_reads_keep_partial_messages() {
+        let (mut server, client) = tokio::io::duplex(1024);
+        let mut reader = FrameReader::new(client);
+        let message = Message::new(
+            Headers::new(1, MessageType::Application, MessageFlags::none()),
+            Some("hello"),
+        );
+        let bytes = message.to_bytes().unwrap();
+        let (first, second) = bytes.split_at(bytes.len() / 2);
+
+        server.write_all(first).await.unwrap();
+        let res = timeout(Duration::from_millis(10), reader.read_frame()).await;
+        assert!(res.is_err(), "read an incomplete message: {res:?}");
+
+        server.write_all(second).await.unwrap();
+        let frame = reader.read_frame().await.unwrap();
+        assert_eq!(frame, bytes[..]);
+        assert_eq!(Message::<&str>::from_bytes(&mut &frame[..]).unwrap(), message);
+    }
+}
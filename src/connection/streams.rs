@@ -0,0 +1,280 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use tokio::sync::mpsc;
+
+use crate::{
+    protocol::{OwnedMessage, FIRST_STREAM_ID},
+    sync::lock,
+    Error, Result,
+};
+
+/// The routing table from stream IDs to the calls and subscriptions waiting for messages on them.
+#[derive(Debug, Clone)]
+pub(crate) struct Streams {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Streams {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                routes: HashMap::new(),
+                next_stream_id: FIRST_STREAM_ID + 1,
+                max_stream_id: i32::MAX,
+                closed: None,
+            })),
+        }
+    }
+
+    /// Allocate a new stream ID.
+    ///
+    /// IDs are handed out in increasing order, wrapping around after `i32::MAX`, skipping the
+    /// handshake's stream ID and the IDs of the calls and subscriptions still in progress.
+    pub(crate) fn next_stream_id(&self) -> Result<i32> {
+        let mut inner = self.inner();
+        // At most every active ID is skipped before a free one is found.
+        for _ in 0..=inner.routes.len() {
+            let stream_id = inner.next_stream_id;
+            inner.next_stream_id =
+                if stream_id >= inner.max_stream_id { FIRST_STREAM_ID + 1 } else { stream_id + 1 };
+            if !inner.routes.contains_key(&stream_id) {
+                return Ok(stream_id);
+            }
+        }
+
+        Err(Error::StreamIdsExhausted)
+    }
+
+    /// Start routing messages on the given stream ID to the returned route.
+    pub(crate) fn register(&self, stream_id: i32) -> Result<Route> {
+        let mut inner = self.inner();
+        if let Some(reason) = &inner.closed {
+            return Err(reason.to_error());
+        }
+        if inner.routes.contains_key(&stream_id) {
+            return Err(Error::Protocol(format!("Stream ID {stream_id} already in use")));
+        }
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let route =
+            Route { stream_id, receiver, sender: sender.downgrade(), streams: self.clone() };
+        inner.routes.insert(stream_id, sender);
+
+        Ok(route)
+    }
+
+    /// Route a message to whoever is waiting for it.
+    ///
+    /// If `last` is `true`, the route is closed after this message. Returns `false` if no one is
+    /// waiting for messages on the stream.
+    pub(crate) fn dispatch(&self, stream_id: i32, message: OwnedMessage, last: bool) -> bool {
+        let mut inner = self.inner();
+        let delivered = match inner.routes.get(&stream_id) {
+            Some(sender) => sender.send(Ok(message)).is_ok(),
+            None => false,
+        };
+        if last || !delivered {
+            inner.routes.remove(&stream_id);
+        }
+
+        delivered
+    }
+
+    /// Deliver an error to the call or subscription on the given stream.
+    pub(crate) fn fail(&self, stream_id: i32, error: Error) {
+        if let Some(sender) = self.inner().routes.get(&stream_id) {
+            let _ = sender.send(Err(error));
+        }
+    }
+
+    /// Fail all pending calls and subscriptions and refuse any new ones.
+    ///
+    /// Only the first call has any effect.
+    pub(crate) fn close(&self, reason: CloseReason) {
+        let mut inner = self.inner();
+        if inner.closed.is_some() {
+            return;
+        }
+        for (_, sender) in inner.routes.drain() {
+            let _ = sender.send(Err(reason.to_error()));
+        }
+        inner.closed = Some(reason);
+    }
+
+    /// Stop routing messages to `route`.
+    fn remove(&self, route: &Route) {
+        let mut inner = self.inner();
+        // Once its stream ended, the ID may have been reused by a new route.
+        let Some(sender) = route.sender.upgrade() else {
+            return;
+        };
+        if inner.routes.get(&route.stream_id).is_some_and(|s| s.same_channel(&sender)) {
+            inner.routes.remove(&route.stream_id);
+        }
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        lock(&self.inner)
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    routes: HashMap<i32, mpsc::UnboundedSender<Result<OwnedMessage>>>,
+    next_stream_id: i32,
+    // Only ever lowered in tests.
+    max_stream_id: i32,
+    closed: Option<CloseReason>,
+}
+
+/// Why the connection was closed.
+#[derive(Debug, Clone)]
+pub(crate) enum CloseReason {
+    /// The socket was closed or could not be read from anymore.
+    Closed,
+    /// The server stopped responding to pings.
+    Dead,
+    /// The server reported a protocol error for the whole connection.
+    ProtocolError(String),
+    /// The server reported an internal error for the whole connection.
+    InternalError(String),
+}
+
+impl CloseReason {
+    /// The error to fail calls and subscriptions with.
+    fn to_error(&self) -> Error {
+        match self {
+            Self::Closed => Error::ConnectionClosed,
+            Self::Dead => Error::ConnectionDead,
+            Self::ProtocolError(e) => Error::Protocol(e.clone()),
+            Self::InternalError(e) => Error::InternalServer(e.clone()),
+        }
+    }
+}
+
+/// The receiving end of a stream.
+///
+/// Dropping it stops the routing of the messages on the stream.
+#[derive(Debug)]
+pub(crate) struct Route {
+    stream_id: i32,
+    receiver: mpsc::UnboundedReceiver<Result<OwnedMessage>>,
+    // To recognize the route's entry in the routing table.
+    sender: mpsc::WeakUnboundedSender<Result<OwnedMessage>>,
+    streams: Streams,
+}
+
+impl Route {
+    /// The stream ID.
+    pub(crate) fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    /// Receive the next message on the stream.
+    ///
+    /// Returns `None` after the last message on the stream.
+    pub(crate) async fn recv(&mut self) -> Option<Result<OwnedMessage>> {
+        self.receiver.recv().await
+    }
+
+    /// Whether no more messages will be routed to the stream, because it was terminated or the
+    /// connection was closed.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.receiver.is_closed()
+    }
+}
+
+impl Drop for Route {
+    fn drop(&mut self) {
+        self.streams.remove(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{CloseReason, Streams};
+    use crate::{
+        protocol::{Headers, Message, MessageFlags, MessageType, OwnedMessage},
+        Error,
+    };
+
+    #[test]
+    fn stream_ids_wrap_around() {
+        let streams = Streams::new();
+        assert_eq!(streams.next_stream_id().unwrap(), 1);
+        assert_eq!(streams.next_stream_id().unwrap(), 2);
+
+        streams.inner().next_stream_id = i32::MAX;
+        assert_eq!(streams.next_stream_id().unwrap(), i32::MAX);
+        // The handshake's stream ID is never handed out.
+        assert_eq!(streams.next_stream_id().unwrap(), 1);
+    }
+
+    #[test]
+    fn active_stream_ids_are_skipped() {
+        let streams = Streams::new();
+        let _route1 = streams.register(1).unwrap();
+        let route2 = streams.register(2).unwrap();
+        let _route4 = streams.register(4).unwrap();
+        assert_eq!(streams.next_stream_id().unwrap(), 3);
+        assert_eq!(streams.next_stream_id().unwrap(), 5);
+
+        drop(route2);
+        streams.inner().next_stream_id = 1;
+        assert_eq!(streams.next_stream_id().unwrap(), 2);
+    }
+
+    #[test]
+    fn stream_ids_exhausted() {
+        let streams = Streams::new();
+        streams.inner().max_stream_id = 3;
+        let routes: Vec<_> = (1..=3).map(|id| streams.register(id).unwrap()).collect();
+        assert!(matches!(streams.next_stream_id(), Err(Error::StreamIdsExhausted)));
+
+        drop(routes);
+        assert_eq!(streams.next_stream_id().unwrap(), 2);
+    }
+
+    #[test]
+    fn routes_close_when_streams_end() {
+        let streams = Streams::new();
+        let message = |stream_id| {
+            let headers = Headers::new(stream_id, MessageType::Application, MessageFlags::none());
+            let bytes = Message::new(headers, Some("event")).to_bytes().unwrap();
+            OwnedMessage::from_bytes(Bytes::from(bytes)).unwrap()
+        };
+
+        let route1 = streams.register(1).unwrap();
+        assert!(streams.dispatch(1, message(1), false));
+        streams.fail(1, Error::ConnectionDead);
+        assert!(!route1.is_closed());
+        assert!(streams.dispatch(1, message(1), true));
+        assert!(route1.is_closed());
+
+        let route2 = streams.register(2).unwrap();
+        streams.close(CloseReason::Closed);
+        assert!(route2.is_closed());
+    }
+
+    #[test]
+    fn reused_stream_ids() {
+        let streams = Streams::new();
+        let message = |stream_id| {
+            let headers = Headers::new(stream_id, MessageType::Application, MessageFlags::none());
+            let bytes = Message::new(headers, Some("event")).to_bytes().unwrap();
+            OwnedMessage::from_bytes(Bytes::from(bytes)).unwrap()
+        };
+
+        // The first route's stream ends, freeing its ID before the route is dropped.
+        let route1 = streams.register(1).unwrap();
+        assert!(streams.dispatch(1, message(1), true));
+        let mut route2 = streams.register(1).unwrap();
+        drop(route1);
+        assert!(streams.dispatch(1, message(1), false));
+        assert!(route2.receiver.try_recv().unwrap().is_ok());
+    }
+}
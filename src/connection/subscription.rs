@@ -0,0 +1,110 @@
+use core::{fmt::Debug, marker::PhantomData};
+use std::sync::Weak;
+
+use tracing::warn;
+
+use super::{parse_response, streams::Route, terminate_stream, Inner};
+use crate::{
+    protocol::{DecodePayload, Message, OwnedMessage},
+    Error, Result,
+};
+
+/// A subscription to a stream of events from the server.
+///
+/// Obtained through [`super::Connection::subscribe`] or one of the specific subscription methods
+/// of [`super::Connection`]. Call [`Subscription::unsubscribe`] to end the subscription. Dropping
+/// the subscription ends it as well, from a background task.
+#[derive(Debug)]
+pub struct Subscription<Event> {
+    route: Route,
+    conn: Weak<Inner>,
+    // Whether the stream has ended, so there's no need to terminate it.
+    ended: bool,
+    _event: PhantomData<fn() -> Event>,
+}
+
+impl<Event> Subscription<Event>
+where
+    Event: for<'de> DecodePayload<'de> + Debug,
+{
+    pub(crate) fn new(route: Route, conn: Weak<Inner>) -> Self {
+        Self { route, conn, ended: false, _event: PhantomData }
+    }
+
+    /// The stream ID of the subscription.
+    pub fn stream_id(&self) -> i32 {
+        self.route.stream_id()
+    }
+
+    /// Wait for the next event.
+    ///
+    /// Returns `None` once the server ends the stream. This method is cancel safe: if the future
+    /// is dropped before completing, no event is lost.
+    pub async fn next(&mut self) -> Option<Result<Message<'static, Event>>> {
+        let message = self.recv().await;
+
+        message.map(|message| message.and_then(|message| parse_response(&message, false)))
+    }
+
+    /// Wait for the server's response to the subscription request and check it for errors.
+    pub(crate) async fn accept<Response>(&mut self) -> Result<()>
+    where
+        Response: for<'de> DecodePayload<'de> + Debug,
+    {
+        let message = self.recv().await.ok_or(Error::ConnectionClosed)??;
+
+        parse_response::<Response>(&message, false).map(drop)
+    }
+
+    /// Don't terminate the stream on drop, since the server doesn't know about it.
+    pub(crate) fn mark_ended(&mut self) {
+        self.ended = true;
+    }
+
+    async fn recv(&mut self) -> Option<Result<OwnedMessage>> {
+        let message = self.route.recv().await;
+        // Once the server terminated the stream or the connection was closed, there's nothing left
+        // to terminate on drop.
+        if message.is_none() || self.route.is_closed() {
+            self.ended = true;
+        }
+
+        message
+    }
+
+    /// End the subscription.
+    ///
+    /// Tells the server to terminate the stream of the subscription. Any events still on their way
+    /// are dropped.
+    pub async fn unsubscribe(mut self) -> Result<()> {
+        self.ended = true;
+        match self.conn.upgrade() {
+            Some(conn) => terminate_stream(&conn, self.stream_id()).await,
+            // No connection, no stream to terminate.
+            None => Ok(()),
+        }
+    }
+}
+
+impl<Event> Drop for Subscription<Event> {
+    fn drop(&mut self) {
+        if self.ended {
+            return;
+        }
+        let Some(conn) = self.conn.upgrade() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("Subscription dropped outside of a runtime, not terminating its stream");
+
+            return;
+        };
+
+        let stream_id = self.route.stream_id();
+        runtime.spawn(async move {
+            if let Err(e) = terminate_stream(&conn, stream_id).await {
+                warn!("Error terminating stream {stream_id}: {e}");
+            }
+        });
+    }
+}
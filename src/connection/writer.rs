@@ -0,0 +1,110 @@
+use core::fmt::Debug;
+use std::sync::Arc;
+
+use tokio::{io::AsyncWriteExt, net::unix::OwnedWriteHalf, sync::Mutex};
+use tracing::trace;
+
+use crate::{
+    protocol::{EncodePayload, Message},
+    Result,
+};
+
+/// The writing half of the socket, along with a buffer reused for encoding messages.
+#[derive(Debug)]
+pub(crate) struct Writer {
+    socket: OwnedWriteHalf,
+    buffer: Vec<u8>,
+    // How much of the message in `buffer` has been written to the socket.
+    written: usize,
+}
+
+impl Writer {
+    pub(crate) fn new(socket: OwnedWriteHalf) -> Self {
+        Self { socket, buffer: Vec::with_capacity(BUFFER_SIZE), written: 0 }
+    }
+
+    /// Write whatever is left of the buffered message to the socket.
+    ///
+    /// Cancel safe: the progress is kept, so a later call picks up where the last one stopped.
+    async fn flush(&mut self) -> Result<()> {
+        while self.written < self.buffer.len() {
+            let written = self.socket.write(&self.buffer[self.written..]).await?;
+            if written == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::WriteZero).into());
+            }
+            self.written += written;
+        }
+
+        self.buffer.clear();
+        self.written = 0;
+        // Don't hold on to the memory of an unusually large message.
+        self.buffer.shrink_to(BUFFER_SIZE);
+
+        Ok(())
+    }
+}
+
+/// Write a message to the socket.
+///
+/// If the returned future is dropped while the message is only partially written, the rest of it
+/// is written before the next message. Otherwise a partially written message would corrupt the
+/// stream.
+pub(crate) async fn write_message<Payload>(
+    writer: &Arc<Mutex<Writer>>,
+    message: Message<'_, Payload>,
+) -> Result<()>
+where
+    Payload: EncodePayload + Debug,
+{
+    let mut writer = writer.lock().await;
+    // Finish the message of a cancelled call first.
+    writer.flush().await?;
+    message.encode_appending(&mut writer.buffer)?;
+    writer.flush().await?;
+    trace!("Sent message: {message:?}");
+
+    Ok(())
+}
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::sync::Arc;
+
+    use tokio::{net::UnixStream, sync::Mutex, time::timeout};
+
+    use super::{write_message, Writer};
+    use crate::{
+        connection::reader::FrameReader,
+        protocol::{Binary, Headers, Message, MessageFlags, MessageType},
+    };
+
+    #[tokio::test]
+    async fn cancelled_writes_are_completed() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let (_, write_half) = client.into_split();
+        let writer = Arc::new(Mutex::new(Writer::new(write_half)));
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let large = Message::new(headers, Some(Binary::new(vec![7; 4 * 1024 * 1024])));
+
+        // Nothing reads from the socket, so the message can't be written in full.
+        let res = timeout(Duration::from_millis(10), write_message(&writer, large.clone())).await;
+        assert!(res.is_err(), "wrote the whole message: {res:?}");
+
+        let mut reader = FrameReader::new(server);
+        let read = tokio::spawn(async move {
+            let first = reader.read_frame().await.unwrap();
+            let second = reader.read_frame().await.unwrap();
+            (first, second)
+        });
+        let headers = Headers::new(2, MessageType::Application, MessageFlags::none());
+        let small = Message::new(headers, Some("small"));
+        write_message(&writer, small.clone()).await.unwrap();
+
+        let (first, second) = read.await.unwrap();
+        assert_eq!(first, large.to_bytes().unwrap()[..]);
+        assert_eq!(second, small.to_bytes().unwrap()[..]);
+    }
+}
@@ -0,0 +1,216 @@
+use core::{num::NonZeroU64, time::Duration};
+
+use crate::protocol::RecheckAfterMs;
+
+/// The policy for deferring component updates.
+///
+/// By default, updates are deferred for as long as they're paused, with the nucleus rechecking
+/// every minute. Set [`DeferralPolicy::with_max_deferral`] to guarantee that a wedged component
+/// can not block deployments forever.
+///
+/// The policy also decides for how long pre-update hooks (see
+/// [`crate::IpcClient::add_pre_update_hook`]) can hold back an update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeferralPolicy {
+    recheck_interval: Duration,
+    max_deferral: Option<Duration>,
+    message: Option<String>,
+    restart_strategy: RestartStrategy,
+    hook_timeout: Duration,
+}
+
+impl DeferralPolicy {
+    /// Create a new policy with the default settings.
+    pub fn new() -> Self {
+        Self {
+            recheck_interval: DEFAULT_RECHECK_INTERVAL,
+            max_deferral: None,
+            message: None,
+            restart_strategy: RestartStrategy::Defer,
+            hook_timeout: DEFAULT_HOOK_TIMEOUT,
+        }
+    }
+
+    /// Set the interval after which the nucleus should recheck if the update can proceed.
+    ///
+    /// The interval is rounded down to whole milliseconds, with a minimum of 1 millisecond.
+    pub fn with_recheck_interval(mut self, interval: Duration) -> Self {
+        self.recheck_interval = interval;
+
+        self
+    }
+
+    /// Set the maximum total time a single deployment can be deferred for.
+    ///
+    /// Once the deployment has been deferred for this long, it's allowed to proceed even if the
+    /// updates are still paused.
+    pub fn with_max_deferral(mut self, max_deferral: Duration) -> Self {
+        self.max_deferral = Some(max_deferral);
+
+        self
+    }
+
+    /// Set the message sent to the nucleus along with each deferral.
+    pub fn with_message<M>(mut self, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        self.message = Some(message.into());
+
+        self
+    }
+
+    /// Set the strategy for updates that restart the nucleus itself.
+    pub fn with_restart_strategy(mut self, strategy: RestartStrategy) -> Self {
+        self.restart_strategy = strategy;
+
+        self
+    }
+
+    /// Set for how long to wait for the pre-update hooks to finish before letting the update
+    /// proceed.
+    pub fn with_hook_timeout(mut self, timeout: Duration) -> Self {
+        self.hook_timeout = timeout;
+
+        self
+    }
+
+    /// The recheck interval.
+    pub fn recheck_interval(&self) -> Duration {
+        self.recheck_interval
+    }
+
+    /// The maximum total time a single deployment can be deferred for, if any.
+    pub fn max_deferral(&self) -> Option<Duration> {
+        self.max_deferral
+    }
+
+    /// The message sent to the nucleus along with each deferral, if any.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// The strategy for updates that restart the nucleus itself.
+    pub fn restart_strategy(&self) -> RestartStrategy {
+        self.restart_strategy
+    }
+
+    /// For how long to wait for the pre-update hooks to finish.
+    pub fn hook_timeout(&self) -> Duration {
+        self.hook_timeout
+    }
+
+    /// Decide how long to defer a deployment that has already been deferred for `deferred_for`.
+    pub(crate) fn recheck_after(
+        &self,
+        deferred_for: Duration,
+        is_ggc_restarting: bool,
+    ) -> RecheckAfterMs {
+        let max_deferral = match (is_ggc_restarting, self.restart_strategy) {
+            (true, RestartStrategy::Proceed) => return RecheckAfterMs::DontDefer,
+            (true, RestartStrategy::DeferUpTo(max)) => {
+                Some(self.max_deferral.map_or(max, |m| m.min(max)))
+            }
+            _ => self.max_deferral,
+        };
+        let recheck_interval = match max_deferral {
+            Some(max) if deferred_for >= max => return RecheckAfterMs::DontDefer,
+            Some(max) => self.recheck_interval.min(max - deferred_for),
+            None => self.recheck_interval,
+        };
+
+        defer_for(recheck_interval)
+    }
+
+    /// The deferral holding back a deployment while its pre-update hooks run, until their timeout.
+    pub(crate) fn hook_deferral(&self) -> RecheckAfterMs {
+        defer_for(self.hook_timeout)
+    }
+}
+
+/// Defer for `duration`, rounded down to whole milliseconds with a minimum of 1 millisecond.
+fn defer_for(duration: Duration) -> RecheckAfterMs {
+    let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+
+    RecheckAfterMs::Defer(NonZeroU64::new(ms).unwrap_or(NonZeroU64::MIN))
+}
+
+impl Default for DeferralPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How to handle updates that also restart the nucleus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartStrategy {
+    /// Defer the update like any other.
+    Defer,
+    /// Never defer the update.
+    Proceed,
+    /// Defer the update, but for no longer than the given total duration.
+    ///
+    /// If the policy also has a maximum deferral set, the shorter of the two applies.
+    DeferUpTo(Duration),
+}
+
+const DEFAULT_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::{DeferralPolicy, RestartStrategy};
+    use crate::protocol::RecheckAfterMs;
+
+    fn defer(ms: u64) -> RecheckAfterMs {
+        RecheckAfterMs::Defer(ms.try_into().unwrap())
+    }
+
+    #[test]
+    fn default_policy_defers_forever() {
+        let policy = DeferralPolicy::default();
+        assert_eq!(policy.recheck_after(Duration::ZERO, false), defer(60_000));
+        assert_eq!(policy.recheck_after(Duration::from_secs(86_400), false), defer(60_000));
+        assert_eq!(policy.recheck_after(Duration::from_secs(86_400), true), defer(60_000));
+    }
+
+    #[test]
+    fn max_deferral() {
+        let policy = DeferralPolicy::new()
+            .with_recheck_interval(Duration::from_secs(10))
+            .with_max_deferral(Duration::from_secs(25));
+        assert_eq!(policy.recheck_after(Duration::ZERO, false), defer(10_000));
+        assert_eq!(policy.recheck_after(Duration::from_secs(20), false), defer(5_000));
+        assert_eq!(policy.recheck_after(Duration::from_secs(25), false), RecheckAfterMs::DontDefer);
+        assert_eq!(policy.recheck_after(Duration::from_secs(30), false), RecheckAfterMs::DontDefer);
+    }
+
+    #[test]
+    fn restart_strategy() {
+        let policy = DeferralPolicy::new().with_restart_strategy(RestartStrategy::Proceed);
+        assert_eq!(policy.recheck_after(Duration::ZERO, false), defer(60_000));
+        assert_eq!(policy.recheck_after(Duration::ZERO, true), RecheckAfterMs::DontDefer);
+
+        let policy = DeferralPolicy::new()
+            .with_max_deferral(Duration::from_secs(600))
+            .with_restart_strategy(RestartStrategy::DeferUpTo(Duration::from_secs(90)));
+        assert_eq!(policy.recheck_after(Duration::from_secs(60), true), defer(30_000));
+        assert_eq!(policy.recheck_after(Duration::from_secs(90), true), RecheckAfterMs::DontDefer);
+        assert_eq!(policy.recheck_after(Duration::from_secs(90), false), defer(60_000));
+    }
+
+    #[test]
+    fn hook_deferral() {
+        assert_eq!(DeferralPolicy::new().hook_deferral(), defer(30_000));
+        let policy = DeferralPolicy::new().with_hook_timeout(Duration::from_millis(1500));
+        assert_eq!(policy.hook_deferral(), defer(1_500));
+    }
+
+    #[test]
+    fn sub_millisecond_interval() {
+        let policy = DeferralPolicy::new().with_recheck_interval(Duration::from_micros(10));
+        assert_eq!(policy.recheck_after(Duration::ZERO, false), defer(1));
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::SystemTime;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::UpdatePauser;
+
+/// A stream of [`DeploymentRecord`]s for the deployments affecting this component.
+///
+/// Obtained through [`crate::IpcClient::track_deployments`]. The component stays subscribed to
+/// component updates as long as the tracker is alive.
+#[derive(Debug)]
+pub struct DeploymentTracker {
+    receiver: broadcast::Receiver<DeploymentRecord>,
+    pauser: UpdatePauser,
+}
+
+impl DeploymentTracker {
+    pub(crate) fn new(
+        receiver: broadcast::Receiver<DeploymentRecord>,
+        pauser: UpdatePauser,
+    ) -> Self {
+        Self { receiver, pauser }
+    }
+
+    /// Wait for the next record.
+    ///
+    /// Returns `None` once no more records can be received, which happens when the component
+    /// updates stop being handled, e.g. because the server ended the subscription or the connection
+    /// failed. If the tracker falls too far behind, the oldest records are skipped.
+    pub async fn next(&mut self) -> Option<DeploymentRecord> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(record) => return Some(record),
+                Err(RecvError::Lagged(n)) => warn!("Deployment tracker skipped {n} record(s)"),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for DeploymentTracker {
+    fn drop(&mut self) {
+        self.pauser.remove_tracker();
+    }
+}
+
+/// A record of a deployment reaching a stage of its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeploymentRecord {
+    deployment_id: Uuid,
+    timestamp: SystemTime,
+    stage: DeploymentStage,
+}
+
+impl DeploymentRecord {
+    pub(crate) fn new(deployment_id: Uuid, stage: DeploymentStage) -> Self {
+        Self { deployment_id, timestamp: SystemTime::now(), stage }
+    }
+
+    /// The ID of the deployment.
+    pub fn deployment_id(&self) -> Uuid {
+        self.deployment_id
+    }
+
+    /// When the deployment reached this stage.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// The stage the deployment reached.
+    pub fn stage(&self) -> DeploymentStage {
+        self.stage
+    }
+}
+
+/// The stage of a deployment's lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeploymentStage {
+    /// The nucleus announced the deployment.
+    Announced {
+        /// Whether the deployment restarts the nucleus.
+        is_ggc_restarting: bool,
+    },
+    /// The deployment was deferred.
+    Deferred {
+        /// How many times the deployment has been deferred so far.
+        count: u32,
+    },
+    /// The deployment was allowed to proceed.
+    Proceeded,
+    /// The deployment completed.
+    Completed,
+}
@@ -1,7 +1,7 @@
 use core::fmt::{self, Display, Formatter};
 use std::io;
 
-use crate::protocol::MessageType;
+use crate::protocol::{ApplicationError, MessageType};
 
 /// Error type for the AWS Greengrass Nucleus IPC client.
 #[derive(Debug)]
@@ -10,8 +10,11 @@ pub enum Error {
     Io(io::Error),
     /// A JSON error occurred.
     Json(serde_json::Error),
-    /// An application error occurred.
-    Application(String),
+    /// A JSON error occurred while decoding a payload with the `simd-json` backend.
+    #[cfg(feature = "simd-json")]
+    SimdJson(simd_json::Error),
+    /// The service returned an error.
+    Application(ApplicationError),
     /// A protocol error occurred.
     Protocol(String),
     /// An internal server error occurred.
@@ -25,6 +28,8 @@ pub enum Error {
     },
     /// Missing header from a message.
     MissingHeader(&'static str),
+    /// A header could not be set.
+    InvalidHeader(String),
     /// Buffer or string size exceeds maximum allowed size.
     BufferTooLarge {
         /// The size of the buffer or string.
@@ -36,8 +41,24 @@ pub enum Error {
     EnvVarNotSet(&'static str),
     /// Checksum mismatch.
     ChecksumMismatch,
+    /// A message exceeds the maximum message or headers size.
+    ///
+    /// See [`crate::protocol::prelude::MAX_MESSAGE_SIZE`] and
+    /// [`crate::protocol::prelude::MAX_HEADERS_SIZE`].
+    FrameTooLarge {
+        /// The total size of the message.
+        total_len: usize,
+        /// The size of the headers of the message.
+        headers_len: usize,
+    },
     /// Connection refused by the server.
     ConnectionRefused,
+    /// The connection to the server was closed.
+    ConnectionClosed,
+    /// The server stopped responding to keep-alive pings.
+    ConnectionDead,
+    /// All stream IDs are in use by calls and subscriptions in progress.
+    StreamIdsExhausted,
 }
 
 /// Result type for the AWS Greengrass Nucleus IPC client.
@@ -48,6 +69,9 @@ impl core::error::Error for Error {
         match self {
             Self::Io(e) => Some(e),
             Self::Json(e) => Some(e),
+            #[cfg(feature = "simd-json")]
+            Self::SimdJson(e) => Some(e),
+            Self::Application(e) => Some(e),
             _ => None,
         }
     }
@@ -65,11 +89,20 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "simd-json")]
+impl From<simd_json::Error> for Error {
+    fn from(e: simd_json::Error) -> Self {
+        Self::SimdJson(e)
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::Io(e) => write!(f, "IO error: {e}"),
             Self::Json(e) => write!(f, "JSON error: {e}"),
+            #[cfg(feature = "simd-json")]
+            Self::SimdJson(e) => write!(f, "JSON error: {e}"),
             Self::Protocol(e) => write!(f, "Protocol Error: {e}"),
             Self::Application(e) => write!(f, "Application Error: {e}"),
             Self::InternalServer(e) => write!(f, "Internal Server Error: {e}"),
@@ -80,6 +113,7 @@ impl Display for Error {
                 )
             }
             Self::MissingHeader(header) => write!(f, "Missing header: `{header}`"),
+            Self::InvalidHeader(e) => write!(f, "Invalid header: {e}"),
             Self::BufferTooLarge { size, max_size } => {
                 write!(
                     f,
@@ -88,7 +122,16 @@ impl Display for Error {
             }
             Self::EnvVarNotSet(var) => write!(f, "Environment variable `{var}` not set"),
             Self::ChecksumMismatch => write!(f, "Checksum mismatch"),
+            Self::FrameTooLarge { total_len, headers_len } => write!(
+                f,
+                "Message too large: {total_len} bytes with {headers_len} bytes of headers"
+            ),
             Self::ConnectionRefused => write!(f, "Connection refused by the server"),
+            Self::ConnectionClosed => write!(f, "Connection closed"),
+            Self::StreamIdsExhausted => write!(f, "All stream IDs are in use"),
+            Self::ConnectionDead => {
+                write!(f, "Connection dead: server stopped responding to pings")
+            }
         }
     }
 }
@@ -6,20 +6,34 @@ mod connection;
 mod error;
 pub mod protocol;
 pub use error::{Error, Result};
+mod deferral_policy;
+mod deployment_tracker;
+pub use deployment_tracker::{DeploymentRecord, DeploymentStage, DeploymentTracker};
 #[doc(hidden)]
 pub mod env;
+pub use deferral_policy::{DeferralPolicy, RestartStrategy};
 mod lifecycle_state;
 pub use lifecycle_state::LifecycleState;
-mod paused_updates;
+mod component_updates;
+pub use component_updates::{UpdatePauseGuard, UpdatePauser};
+mod sync;
 
-pub use connection::Connection;
-use tokio::task::JoinHandle;
+use core::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
-#[derive(Debug)]
+pub use connection::{Connection, KeepAlive, Subscription};
+use protocol::PreComponentUpdateEvent;
+
+#[derive(Debug, Clone)]
 /// The IPC client for interacting with the Greengrass Nucleus.
+///
+/// The client can be cloned cheaply and shared between tasks. All clones share the same
+/// connection, over which any number of calls can be in flight concurrently.
 pub struct IpcClient {
     conn: Connection,
-    component_update_task: Option<JoinHandle<()>>,
+    update_pauser: UpdatePauser,
+    pause_guard: Arc<Mutex<Option<UpdatePauseGuard>>>,
 }
 
 impl IpcClient {
@@ -27,30 +41,39 @@ impl IpcClient {
     pub async fn new(socket_path: &'static str, auth_token: &'static str) -> Result<Self> {
         let conn = Connection::new(socket_path, auth_token).await?;
 
-        Ok(Self { conn, component_update_task: None })
+        Ok(Self::with_connection(conn))
     }
 
     /// Create a new IPC client, fetching the socket path and auth token from the environment.
     pub async fn from_env() -> Result<Self> {
         let conn = Connection::from_env().await?;
 
-        Ok(Self { conn, component_update_task: None })
+        Ok(Self::with_connection(conn))
+    }
+
+    /// A handle for pausing component updates.
+    ///
+    /// Unlike [`IpcClient::pause_component_update`], the returned handle can be cloned and shared
+    /// between tasks, each of which can hold its own [`UpdatePauseGuard`]. The updates stay paused
+    /// as long as any guard is alive, including the one held by this client when
+    /// [`IpcClient::pause_component_update`] was called.
+    pub fn update_pauser(&self) -> UpdatePauser {
+        self.update_pauser.clone()
     }
 
     /// Pause component updates.
     ///
     /// After this call the, the component updates will be paused until
-    /// [`IpcClient::resume_component_update`] is called.
-    pub async fn pause_component_update(&mut self) -> Result<()> {
-        if self.component_update_task.is_some() {
+    /// [`IpcClient::resume_component_update`] is called. The pause is shared by all clones of the
+    /// client.
+    pub async fn pause_component_update(&self) -> Result<()> {
+        let mut pause_guard = self.pause_guard.lock().await;
+        if pause_guard.is_some() {
             return Ok(());
         }
 
-        let paused_updates =
-            paused_updates::PausedUpdates::new(self.conn.socket_path(), self.conn.auth_token())
-                .await?;
-        let component_update_task = tokio::spawn(paused_updates.keep_paused());
-        assert!(self.component_update_task.replace(component_update_task).is_none());
+        let guard = self.update_pauser.pause().await?;
+        assert!(pause_guard.replace(guard).is_none());
 
         Ok(())
     }
@@ -58,27 +81,64 @@ impl IpcClient {
     /// Resume component updates.
     ///
     /// This will resume the component updates that were paused by calling
-    /// [`IpcClient::pause_component_update`].
-    pub async fn resume_component_update(&mut self) -> Result<()> {
-        if let Some(handle) = self.component_update_task.take() {
-            handle.abort();
-        }
+    /// [`IpcClient::pause_component_update`]. The updates remain paused if any other
+    /// [`UpdatePauseGuard`] obtained through [`IpcClient::update_pauser`] is still alive.
+    ///
+    /// If an update is currently being deferred, the nucleus is told to proceed with it right away
+    /// instead of waiting for the next recheck. The same happens when the last clone of the client
+    /// is dropped.
+    pub async fn resume_component_update(&self) -> Result<()> {
+        self.pause_guard.lock().await.take();
 
         Ok(())
     }
 
+    /// Register a hook to run when a component update is about to happen.
+    ///
+    /// This is useful for checkpointing, e.g. flushing buffered telemetry or persisting session
+    /// state. The hooks run concurrently when the nucleus announces a deployment and the update is
+    /// deferred until every hook finishes or the [`DeferralPolicy::hook_timeout`] passes. Use
+    /// [`PreComponentUpdateEvent::is_ggc_restarting`] to find out if the nucleus itself is going to
+    /// restart.
+    ///
+    /// Each hook runs once per deployment, even if the update is paused and the nucleus announces
+    /// it again on each recheck.
+    pub async fn add_pre_update_hook<F, Fut>(&self, hook: F) -> Result<()>
+    where
+        F: Fn(PreComponentUpdateEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hook: component_updates::PreUpdateHook = Arc::new(move |event| Box::pin(hook(event)));
+
+        self.update_pauser.add_pre_update_hook(hook).await
+    }
+
+    /// Track the deployments affecting this component.
+    ///
+    /// The returned tracker yields a [`DeploymentRecord`] each time a deployment is announced,
+    /// deferred, allowed to proceed or completed, which makes it suitable for keeping an audit log
+    /// of the updates on the device.
+    pub async fn track_deployments(&self) -> Result<DeploymentTracker> {
+        self.update_pauser.track_deployments().await
+    }
+
+    /// Set up periodic pings to detect a dead connection, or stop them with `None`.
+    ///
+    /// See [`Connection::set_keep_alive`].
+    pub fn set_keep_alive(&self, keep_alive: Option<KeepAlive>) {
+        self.conn.set_keep_alive(keep_alive);
+    }
+
     /// Manually update the state of the component.
     ///
     /// This is useful when you want to update the state of the component manually.
-    pub async fn update_state(&mut self, state: LifecycleState) -> Result<()> {
+    pub async fn update_state(&self, state: LifecycleState) -> Result<()> {
         self.conn.update_state(state).await
     }
-}
 
-impl Drop for IpcClient {
-    fn drop(&mut self) {
-        if let Some(handle) = self.component_update_task.take() {
-            handle.abort();
-        }
+    fn with_connection(conn: Connection) -> Self {
+        let update_pauser = UpdatePauser::new(conn.clone());
+
+        Self { conn, update_pauser, pause_guard: Arc::new(Mutex::new(None)) }
     }
 }
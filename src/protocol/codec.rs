@@ -0,0 +1,136 @@
+//! Framing of messages in a byte stream.
+
+use bytes::{Buf, BytesMut};
+
+use crate::{Error, Result};
+
+use super::{
+    prelude::{Prelude, MAX_HEADERS_SIZE, SIZE},
+    Headers,
+};
+
+/// A codec splitting a byte stream into messages, based on the length in their [`Prelude`].
+///
+/// With the `codec` feature enabled, this implements the `tokio_util::codec` `Decoder` and
+/// `Encoder` traits, so messages can be read and written through `Framed` over any
+/// `AsyncRead + AsyncWrite`. The decoder yields an [`super::OwnedMessage`] sharing the read
+/// buffer, while the encoder takes a [`super::Message`].
+///
+/// Messages exceeding [`super::prelude::MAX_MESSAGE_SIZE`] are never buffered. The decoder fails
+/// with [`Error::FrameTooLarge`] and skips the message if it's called again, but note that
+/// `Framed` stops after the first error.
+#[derive(Debug, Default)]
+pub struct MessageCodec {
+    // The bytes left to skip of a message that's too large.
+    skipping: usize,
+}
+
+impl MessageCodec {
+    /// Create a new codec.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Split the next message off `src`, if it's complete.
+    ///
+    /// Messages that are too large are consumed as they come in and reported as
+    /// [`Decoded::TooLarge`] once their headers are known.
+    pub(crate) fn decode_frame(&mut self, src: &mut BytesMut) -> Result<Option<Decoded>> {
+        if self.skipping > 0 {
+            let skipped = self.skipping.min(src.len());
+            src.advance(skipped);
+            self.skipping -= skipped;
+            if self.skipping > 0 {
+                return Ok(None);
+            }
+        }
+
+        if src.len() < SIZE {
+            src.reserve(SIZE - src.len());
+
+            return Ok(None);
+        }
+        let prelude = match Prelude::from_bytes(&mut &src[..SIZE]) {
+            Ok(prelude) => prelude,
+            Err(Error::FrameTooLarge { total_len, headers_len }) => {
+                let mut stream_id = None;
+                if headers_len <= MAX_HEADERS_SIZE {
+                    let Some(mut headers_bytes) = src.get(SIZE..SIZE + headers_len) else {
+                        src.reserve(SIZE + headers_len - src.len());
+
+                        return Ok(None);
+                    };
+                    stream_id = Headers::from_bytes(&mut headers_bytes).ok().map(|h| h.stream_id());
+                }
+                let skipped = total_len.min(src.len());
+                src.advance(skipped);
+                self.skipping = total_len - skipped;
+
+                return Ok(Some(Decoded::TooLarge { total_len, headers_len, stream_id }));
+            }
+            Err(e) => return Err(e),
+        };
+
+        if src.len() < prelude.total_len() {
+            src.reserve(prelude.total_len() - src.len());
+
+            return Ok(None);
+        }
+
+        Ok(Some(Decoded::Frame(src.split_to(prelude.total_len()))))
+    }
+}
+
+/// The outcome of decoding the next message.
+#[derive(Debug)]
+pub(crate) enum Decoded {
+    /// The complete bytes of a message.
+    Frame(BytesMut),
+    /// A message exceeding the size limits, which is skipped.
+    TooLarge {
+        total_len: usize,
+        headers_len: usize,
+        /// The stream ID of the message, if its headers could be read.
+        stream_id: Option<i32>,
+    },
+}
+
+#[cfg(feature = "codec")]
+mod tokio_codec {
+    use core::fmt::Debug;
+
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use super::{Decoded, MessageCodec};
+    use crate::{
+        protocol::{EncodePayload, Message, OwnedMessage},
+        Error, Result,
+    };
+
+    impl Decoder for MessageCodec {
+        type Item = OwnedMessage;
+        type Error = Error;
+
+        fn decode(&mut self, src: &mut BytesMut) -> Result<Option<OwnedMessage>> {
+            match self.decode_frame(src)? {
+                Some(Decoded::Frame(frame)) => OwnedMessage::from_bytes(frame.freeze()).map(Some),
+                Some(Decoded::TooLarge { total_len, headers_len, .. }) => {
+                    Err(Error::FrameTooLarge { total_len, headers_len })
+                }
+                None => Ok(None),
+            }
+        }
+    }
+
+    impl<Payload> Encoder<Message<'_, Payload>> for MessageCodec
+    where
+        Payload: EncodePayload + Debug,
+    {
+        type Error = Error;
+
+        fn encode(&mut self, message: Message<'_, Payload>, dst: &mut BytesMut) -> Result<()> {
+            message.encode_appending(dst).map(drop)
+        }
+    }
+}
@@ -0,0 +1,1980 @@
+//! Types and operations generated from the Greengrass IPC Smithy model.
+//!
+//! Generated by `cargo xtask codegen`, do not edit by hand.
+#![allow(unused_imports, clippy::large_enum_variant, clippy::upper_case_acronyms)]
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ErrorCode, ModeledError, Operation, StreamingOperation};
+use crate::{Connection, Result, Subscription};
+/// The `AuthorizeClientDeviceActionRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuthorizeClientDeviceActionRequest {
+    /// The `clientDeviceAuthToken` member.
+    #[serde(rename = "clientDeviceAuthToken")]
+    pub client_device_auth_token: String,
+    /// The `operation` member.
+    #[serde(rename = "operation")]
+    pub operation: String,
+    /// The `resource` member.
+    #[serde(rename = "resource")]
+    pub resource: String,
+}
+
+/// The `AuthorizeClientDeviceActionResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuthorizeClientDeviceActionResponse {
+    /// The `isAuthorized` member.
+    #[serde(rename = "isAuthorized")]
+    pub is_authorized: bool,
+}
+
+/// The `BinaryMessage` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BinaryMessage {
+    /// The `context` member.
+    #[serde(rename = "context", default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<MessageContext>,
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// The `CancelLocalDeploymentRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CancelLocalDeploymentRequest {
+    /// The `deploymentId` member.
+    #[serde(rename = "deploymentId", default, skip_serializing_if = "Option::is_none")]
+    pub deployment_id: Option<String>,
+}
+
+/// The `CancelLocalDeploymentResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CancelLocalDeploymentResponse {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// The `CertificateOptions` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CertificateOptions {
+    /// The `certificateType` member.
+    #[serde(rename = "certificateType")]
+    pub certificate_type: CertificateType,
+}
+
+/// The `CertificateType` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CertificateType {
+    /// `SERVER`.
+    #[serde(rename = "SERVER")]
+    Server,
+}
+
+/// The `CertificateUpdate` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CertificateUpdate {
+    /// The `caCertificates` member.
+    #[serde(rename = "caCertificates", default, skip_serializing_if = "Option::is_none")]
+    pub ca_certificates: Option<Vec<String>>,
+    /// The `certificate` member.
+    #[serde(rename = "certificate", default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<String>,
+    /// The `privateKey` member.
+    #[serde(rename = "privateKey", default, skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<String>,
+    /// The `publicKey` member.
+    #[serde(rename = "publicKey", default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+/// The `CertificateUpdateEvent` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CertificateUpdateEvent {
+    /// The `certificateUpdate` member.
+    #[serde(rename = "certificateUpdate")]
+    CertificateUpdate(CertificateUpdate),
+}
+
+/// The `ClientDeviceCredential` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ClientDeviceCredential {
+    /// The `clientDeviceCertificate` member.
+    #[serde(rename = "clientDeviceCertificate")]
+    ClientDeviceCertificate(String),
+}
+
+/// The `ComponentDetails` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ComponentDetails {
+    /// The `componentName` member.
+    #[serde(rename = "componentName")]
+    pub component_name: String,
+    /// The `configuration` member.
+    #[serde(rename = "configuration", default, skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<HashMap<String, serde_json::Value>>,
+    /// The `state` member.
+    #[serde(rename = "state")]
+    pub state: LifecycleState,
+    /// The `version` member.
+    #[serde(rename = "version")]
+    pub version: String,
+}
+
+/// The `ComponentNotFoundError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ComponentNotFoundError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for ComponentNotFoundError {
+    const CODE: ErrorCode = ErrorCode::ComponentNotFoundError;
+}
+
+/// The `ConfigurationUpdateEvent` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfigurationUpdateEvent {
+    /// The `componentName` member.
+    #[serde(rename = "componentName")]
+    pub component_name: String,
+    /// The `keyPath` member.
+    #[serde(rename = "keyPath")]
+    pub key_path: Vec<String>,
+}
+
+/// The `ConfigurationUpdateEvents` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ConfigurationUpdateEvents {
+    /// The `configurationUpdateEvent` member.
+    #[serde(rename = "configurationUpdateEvent")]
+    ConfigurationUpdateEvent(ConfigurationUpdateEvent),
+}
+
+/// The `ConfigurationValidityReport` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfigurationValidityReport {
+    /// The `deploymentId` member.
+    #[serde(rename = "deploymentId")]
+    pub deployment_id: String,
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The `status` member.
+    #[serde(rename = "status")]
+    pub status: ConfigurationValidityStatus,
+}
+
+/// The `ConfigurationValidityStatus` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigurationValidityStatus {
+    /// `ACCEPTED`.
+    #[serde(rename = "ACCEPTED")]
+    Accepted,
+    /// `REJECTED`.
+    #[serde(rename = "REJECTED")]
+    Rejected,
+}
+
+/// The `ConflictError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConflictError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for ConflictError {
+    const CODE: ErrorCode = ErrorCode::ConflictError;
+}
+
+/// The `CreateDebugPasswordRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreateDebugPasswordRequest {}
+
+/// The `CreateDebugPasswordResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreateDebugPasswordResponse {
+    /// The `certificateSHA1Hash` member.
+    #[serde(rename = "certificateSHA1Hash", default, skip_serializing_if = "Option::is_none")]
+    pub certificate_sha1_hash: Option<String>,
+    /// The `certificateSHA256Hash` member.
+    #[serde(rename = "certificateSHA256Hash", default, skip_serializing_if = "Option::is_none")]
+    pub certificate_sha256_hash: Option<String>,
+    /// The `password` member.
+    #[serde(rename = "password")]
+    pub password: String,
+    /// The `passwordExpiration` member.
+    #[serde(rename = "passwordExpiration")]
+    pub password_expiration: f64,
+    /// The `username` member.
+    #[serde(rename = "username")]
+    pub username: String,
+}
+
+/// The `CreateLocalDeploymentRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreateLocalDeploymentRequest {
+    /// The `artifactsDirectoryPath` member.
+    #[serde(rename = "artifactsDirectoryPath", default, skip_serializing_if = "Option::is_none")]
+    pub artifacts_directory_path: Option<String>,
+    /// The `componentToConfiguration` member.
+    #[serde(rename = "componentToConfiguration", default, skip_serializing_if = "Option::is_none")]
+    pub component_to_configuration: Option<HashMap<String, HashMap<String, serde_json::Value>>>,
+    /// The `componentToRunWithInfo` member.
+    #[serde(rename = "componentToRunWithInfo", default, skip_serializing_if = "Option::is_none")]
+    pub component_to_run_with_info: Option<HashMap<String, RunWithInfo>>,
+    /// The `failureHandlingPolicy` member.
+    #[serde(rename = "failureHandlingPolicy", default, skip_serializing_if = "Option::is_none")]
+    pub failure_handling_policy: Option<FailureHandlingPolicy>,
+    /// The `groupName` member.
+    #[serde(rename = "groupName", default, skip_serializing_if = "Option::is_none")]
+    pub group_name: Option<String>,
+    /// The `recipeDirectoryPath` member.
+    #[serde(rename = "recipeDirectoryPath", default, skip_serializing_if = "Option::is_none")]
+    pub recipe_directory_path: Option<String>,
+    /// The `rootComponentVersionsToAdd` member.
+    #[serde(
+        rename = "rootComponentVersionsToAdd",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub root_component_versions_to_add: Option<HashMap<String, String>>,
+    /// The `rootComponentsToRemove` member.
+    #[serde(rename = "rootComponentsToRemove", default, skip_serializing_if = "Option::is_none")]
+    pub root_components_to_remove: Option<Vec<String>>,
+}
+
+/// The `CreateLocalDeploymentResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreateLocalDeploymentResponse {
+    /// The `deploymentId` member.
+    #[serde(rename = "deploymentId", default, skip_serializing_if = "Option::is_none")]
+    pub deployment_id: Option<String>,
+}
+
+/// The `CredentialDocument` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CredentialDocument {
+    /// The `mqttCredential` member.
+    #[serde(rename = "mqttCredential")]
+    MqttCredential(MQTTCredential),
+}
+
+/// The `DeleteThingShadowRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeleteThingShadowRequest {
+    /// The `shadowName` member.
+    #[serde(rename = "shadowName", default, skip_serializing_if = "Option::is_none")]
+    pub shadow_name: Option<String>,
+    /// The `thingName` member.
+    #[serde(rename = "thingName")]
+    pub thing_name: String,
+}
+
+/// The `DeleteThingShadowResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeleteThingShadowResponse {
+    /// The `payload` member.
+    #[serde(rename = "payload")]
+    pub payload: String,
+}
+
+/// The `DeploymentStatus` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeploymentStatus {
+    /// `QUEUED`.
+    #[serde(rename = "QUEUED")]
+    Queued,
+    /// `IN_PROGRESS`.
+    #[serde(rename = "IN_PROGRESS")]
+    InProgress,
+    /// `SUCCEEDED`.
+    #[serde(rename = "SUCCEEDED")]
+    Succeeded,
+    /// `FAILED`.
+    #[serde(rename = "FAILED")]
+    Failed,
+    /// `CANCELED`.
+    #[serde(rename = "CANCELED")]
+    Canceled,
+}
+
+/// The `DeploymentStatusDetails` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeploymentStatusDetails {
+    /// The `deploymentErrorStack` member.
+    #[serde(rename = "deploymentErrorStack", default, skip_serializing_if = "Option::is_none")]
+    pub deployment_error_stack: Option<Vec<String>>,
+    /// The `deploymentErrorTypes` member.
+    #[serde(rename = "deploymentErrorTypes", default, skip_serializing_if = "Option::is_none")]
+    pub deployment_error_types: Option<Vec<String>>,
+    /// The `deploymentFailureCause` member.
+    #[serde(rename = "deploymentFailureCause", default, skip_serializing_if = "Option::is_none")]
+    pub deployment_failure_cause: Option<String>,
+    /// The `detailedDeploymentStatus` member.
+    #[serde(rename = "detailedDeploymentStatus")]
+    pub detailed_deployment_status: DetailedDeploymentStatus,
+}
+
+/// The `DetailedDeploymentStatus` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetailedDeploymentStatus {
+    /// `SUCCESSFUL`.
+    #[serde(rename = "SUCCESSFUL")]
+    Successful,
+    /// `FAILED_NO_STATE_CHANGE`.
+    #[serde(rename = "FAILED_NO_STATE_CHANGE")]
+    FailedNoStateChange,
+    /// `FAILED_ROLLBACK_NOT_REQUESTED`.
+    #[serde(rename = "FAILED_ROLLBACK_NOT_REQUESTED")]
+    FailedRollbackNotRequested,
+    /// `FAILED_ROLLBACK_COMPLETE`.
+    #[serde(rename = "FAILED_ROLLBACK_COMPLETE")]
+    FailedRollbackComplete,
+    /// `REJECTED`.
+    #[serde(rename = "REJECTED")]
+    Rejected,
+}
+
+/// The `FailedUpdateConditionCheckError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FailedUpdateConditionCheckError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for FailedUpdateConditionCheckError {
+    const CODE: ErrorCode = ErrorCode::FailedUpdateConditionCheckError;
+}
+
+/// The `FailureHandlingPolicy` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureHandlingPolicy {
+    /// `ROLLBACK`.
+    #[serde(rename = "ROLLBACK")]
+    Rollback,
+    /// `DO_NOTHING`.
+    #[serde(rename = "DO_NOTHING")]
+    DoNothing,
+}
+
+/// The `GetClientDeviceAuthTokenRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetClientDeviceAuthTokenRequest {
+    /// The `credential` member.
+    #[serde(rename = "credential")]
+    pub credential: CredentialDocument,
+}
+
+/// The `GetClientDeviceAuthTokenResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetClientDeviceAuthTokenResponse {
+    /// The `clientDeviceAuthToken` member.
+    #[serde(rename = "clientDeviceAuthToken")]
+    pub client_device_auth_token: String,
+}
+
+/// The `GetComponentDetailsRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetComponentDetailsRequest {
+    /// The `componentName` member.
+    #[serde(rename = "componentName")]
+    pub component_name: String,
+}
+
+/// The `GetComponentDetailsResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetComponentDetailsResponse {
+    /// The `componentDetails` member.
+    #[serde(rename = "componentDetails")]
+    pub component_details: ComponentDetails,
+}
+
+/// The `GetConfigurationRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetConfigurationRequest {
+    /// The `componentName` member.
+    #[serde(rename = "componentName", default, skip_serializing_if = "Option::is_none")]
+    pub component_name: Option<String>,
+    /// The `keyPath` member.
+    #[serde(rename = "keyPath")]
+    pub key_path: Vec<String>,
+}
+
+/// The `GetConfigurationResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetConfigurationResponse {
+    /// The `componentName` member.
+    #[serde(rename = "componentName", default, skip_serializing_if = "Option::is_none")]
+    pub component_name: Option<String>,
+    /// The `value` member.
+    #[serde(rename = "value", default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// The `GetLocalDeploymentStatusRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetLocalDeploymentStatusRequest {
+    /// The `deploymentId` member.
+    #[serde(rename = "deploymentId")]
+    pub deployment_id: String,
+}
+
+/// The `GetLocalDeploymentStatusResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetLocalDeploymentStatusResponse {
+    /// The `deployment` member.
+    #[serde(rename = "deployment")]
+    pub deployment: LocalDeployment,
+}
+
+/// The `GetSecretValueRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetSecretValueRequest {
+    /// The `refresh` member.
+    #[serde(rename = "refresh", default, skip_serializing_if = "Option::is_none")]
+    pub refresh: Option<bool>,
+    /// The `secretId` member.
+    #[serde(rename = "secretId")]
+    pub secret_id: String,
+    /// The `versionId` member.
+    #[serde(rename = "versionId", default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    /// The `versionStage` member.
+    #[serde(rename = "versionStage", default, skip_serializing_if = "Option::is_none")]
+    pub version_stage: Option<String>,
+}
+
+/// The `GetSecretValueResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetSecretValueResponse {
+    /// The `secretId` member.
+    #[serde(rename = "secretId")]
+    pub secret_id: String,
+    /// The `secretValue` member.
+    #[serde(rename = "secretValue")]
+    pub secret_value: SecretValue,
+    /// The `versionId` member.
+    #[serde(rename = "versionId")]
+    pub version_id: String,
+    /// The `versionStage` member.
+    #[serde(rename = "versionStage")]
+    pub version_stage: Vec<String>,
+}
+
+/// The `GetThingShadowRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetThingShadowRequest {
+    /// The `shadowName` member.
+    #[serde(rename = "shadowName", default, skip_serializing_if = "Option::is_none")]
+    pub shadow_name: Option<String>,
+    /// The `thingName` member.
+    #[serde(rename = "thingName")]
+    pub thing_name: String,
+}
+
+/// The `GetThingShadowResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetThingShadowResponse {
+    /// The `payload` member.
+    #[serde(rename = "payload")]
+    pub payload: String,
+}
+
+/// The `InvalidArgumentsError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InvalidArgumentsError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for InvalidArgumentsError {
+    const CODE: ErrorCode = ErrorCode::InvalidArgumentsError;
+}
+
+/// The `InvalidArtifactsDirectoryPathError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InvalidArtifactsDirectoryPathError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for InvalidArtifactsDirectoryPathError {
+    const CODE: ErrorCode = ErrorCode::InvalidArtifactsDirectoryPathError;
+}
+
+/// The `InvalidClientDeviceAuthTokenError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InvalidClientDeviceAuthTokenError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for InvalidClientDeviceAuthTokenError {
+    const CODE: ErrorCode = ErrorCode::InvalidClientDeviceAuthTokenError;
+}
+
+/// The `InvalidCredentialError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InvalidCredentialError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for InvalidCredentialError {
+    const CODE: ErrorCode = ErrorCode::InvalidCredentialError;
+}
+
+/// The `InvalidRecipeDirectoryPathError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InvalidRecipeDirectoryPathError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for InvalidRecipeDirectoryPathError {
+    const CODE: ErrorCode = ErrorCode::InvalidRecipeDirectoryPathError;
+}
+
+/// The `InvalidTokenError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InvalidTokenError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for InvalidTokenError {
+    const CODE: ErrorCode = ErrorCode::InvalidTokenError;
+}
+
+/// The `IoTCoreMessage` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum IoTCoreMessage {
+    /// The `message` member.
+    #[serde(rename = "message")]
+    Message(MQTTMessage),
+}
+
+/// The `JsonMessage` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JsonMessage {
+    /// The `context` member.
+    #[serde(rename = "context", default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<MessageContext>,
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<serde_json::Value>,
+}
+
+/// The `LifecycleState` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LifecycleState {
+    /// `RUNNING`.
+    #[serde(rename = "RUNNING")]
+    Running,
+    /// `ERRORED`.
+    #[serde(rename = "ERRORED")]
+    Errored,
+    /// `NEW`.
+    #[serde(rename = "NEW")]
+    New,
+    /// `FINISHED`.
+    #[serde(rename = "FINISHED")]
+    Finished,
+    /// `INSTALLED`.
+    #[serde(rename = "INSTALLED")]
+    Installed,
+    /// `BROKEN`.
+    #[serde(rename = "BROKEN")]
+    Broken,
+    /// `STARTING`.
+    #[serde(rename = "STARTING")]
+    Starting,
+    /// `STOPPING`.
+    #[serde(rename = "STOPPING")]
+    Stopping,
+}
+
+/// The `ListComponentsRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListComponentsRequest {}
+
+/// The `ListComponentsResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListComponentsResponse {
+    /// The `components` member.
+    #[serde(rename = "components", default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<ComponentDetails>>,
+}
+
+/// The `ListLocalDeploymentsRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListLocalDeploymentsRequest {}
+
+/// The `ListLocalDeploymentsResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListLocalDeploymentsResponse {
+    /// The `localDeployments` member.
+    #[serde(rename = "localDeployments", default, skip_serializing_if = "Option::is_none")]
+    pub local_deployments: Option<Vec<LocalDeployment>>,
+}
+
+/// The `ListNamedShadowsForThingRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListNamedShadowsForThingRequest {
+    /// The `nextToken` member.
+    #[serde(rename = "nextToken", default, skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+    /// The `pageSize` member.
+    #[serde(rename = "pageSize", default, skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<i32>,
+    /// The `thingName` member.
+    #[serde(rename = "thingName")]
+    pub thing_name: String,
+}
+
+/// The `ListNamedShadowsForThingResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListNamedShadowsForThingResponse {
+    /// The `nextToken` member.
+    #[serde(rename = "nextToken", default, skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+    /// The `results` member.
+    #[serde(rename = "results")]
+    pub results: Vec<String>,
+    /// The `timestamp` member.
+    #[serde(rename = "timestamp")]
+    pub timestamp: f64,
+}
+
+/// The `LocalDeployment` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LocalDeployment {
+    /// The `createdOn` member.
+    #[serde(rename = "createdOn", default, skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<String>,
+    /// The `deploymentId` member.
+    #[serde(rename = "deploymentId")]
+    pub deployment_id: String,
+    /// The `deploymentStatusDetails` member.
+    #[serde(rename = "deploymentStatusDetails", default, skip_serializing_if = "Option::is_none")]
+    pub deployment_status_details: Option<DeploymentStatusDetails>,
+    /// The `status` member.
+    #[serde(rename = "status")]
+    pub status: DeploymentStatus,
+}
+
+/// The `MQTTCredential` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MQTTCredential {
+    /// The `certificatePem` member.
+    #[serde(rename = "certificatePem", default, skip_serializing_if = "Option::is_none")]
+    pub certificate_pem: Option<String>,
+    /// The `clientId` member.
+    #[serde(rename = "clientId", default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// The `password` member.
+    #[serde(rename = "password", default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// The `username` member.
+    #[serde(rename = "username", default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+/// The `MQTTMessage` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MQTTMessage {
+    /// The `contentType` member.
+    #[serde(rename = "contentType", default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// The `correlationData` member.
+    #[serde(rename = "correlationData", default, skip_serializing_if = "Option::is_none")]
+    pub correlation_data: Option<String>,
+    /// The `messageExpiryIntervalSeconds` member.
+    #[serde(
+        rename = "messageExpiryIntervalSeconds",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub message_expiry_interval_seconds: Option<i64>,
+    /// The `payload` member.
+    #[serde(rename = "payload", default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    /// The `payloadFormat` member.
+    #[serde(rename = "payloadFormat", default, skip_serializing_if = "Option::is_none")]
+    pub payload_format: Option<PayloadFormat>,
+    /// The `responseTopic` member.
+    #[serde(rename = "responseTopic", default, skip_serializing_if = "Option::is_none")]
+    pub response_topic: Option<String>,
+    /// The `retain` member.
+    #[serde(rename = "retain", default, skip_serializing_if = "Option::is_none")]
+    pub retain: Option<bool>,
+    /// The `topicName` member.
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    /// The `userProperties` member.
+    #[serde(rename = "userProperties", default, skip_serializing_if = "Option::is_none")]
+    pub user_properties: Option<Vec<UserProperty>>,
+}
+
+/// The `MessageContext` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MessageContext {
+    /// The `topic` member.
+    #[serde(rename = "topic", default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+}
+
+/// The `Metric` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Metric {
+    /// The `name` member.
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The `unit` member.
+    #[serde(rename = "unit")]
+    pub unit: MetricUnitType,
+    /// The `value` member.
+    #[serde(rename = "value")]
+    pub value: f64,
+}
+
+/// The `MetricUnitType` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetricUnitType {
+    /// `BYTES`.
+    #[serde(rename = "BYTES")]
+    Bytes,
+    /// `BYTES_PER_SECOND`.
+    #[serde(rename = "BYTES_PER_SECOND")]
+    BytesPerSecond,
+    /// `COUNT`.
+    #[serde(rename = "COUNT")]
+    Count,
+    /// `COUNT_PER_SECOND`.
+    #[serde(rename = "COUNT_PER_SECOND")]
+    CountPerSecond,
+    /// `MEGABYTES`.
+    #[serde(rename = "MEGABYTES")]
+    Megabytes,
+    /// `SECONDS`.
+    #[serde(rename = "SECONDS")]
+    Seconds,
+}
+
+/// The `PauseComponentRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PauseComponentRequest {
+    /// The `componentName` member.
+    #[serde(rename = "componentName")]
+    pub component_name: String,
+}
+
+/// The `PauseComponentResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PauseComponentResponse {}
+
+/// The `PayloadFormat` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PayloadFormat {
+    /// `0`.
+    #[serde(rename = "0")]
+    Bytes,
+    /// `1`.
+    #[serde(rename = "1")]
+    Utf8,
+}
+
+/// The `PublishMessage` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PublishMessage {
+    /// The `binaryMessage` member.
+    #[serde(rename = "binaryMessage")]
+    BinaryMessage(BinaryMessage),
+    /// The `jsonMessage` member.
+    #[serde(rename = "jsonMessage")]
+    JsonMessage(JsonMessage),
+}
+
+/// The `PublishToIoTCoreRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PublishToIoTCoreRequest {
+    /// The `contentType` member.
+    #[serde(rename = "contentType", default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// The `correlationData` member.
+    #[serde(rename = "correlationData", default, skip_serializing_if = "Option::is_none")]
+    pub correlation_data: Option<String>,
+    /// The `messageExpiryIntervalSeconds` member.
+    #[serde(
+        rename = "messageExpiryIntervalSeconds",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub message_expiry_interval_seconds: Option<i64>,
+    /// The `payload` member.
+    #[serde(rename = "payload", default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    /// The `payloadFormat` member.
+    #[serde(rename = "payloadFormat", default, skip_serializing_if = "Option::is_none")]
+    pub payload_format: Option<PayloadFormat>,
+    /// The `qos` member.
+    #[serde(rename = "qos")]
+    pub qos: QOS,
+    /// The `responseTopic` member.
+    #[serde(rename = "responseTopic", default, skip_serializing_if = "Option::is_none")]
+    pub response_topic: Option<String>,
+    /// The `retain` member.
+    #[serde(rename = "retain", default, skip_serializing_if = "Option::is_none")]
+    pub retain: Option<bool>,
+    /// The `topicName` member.
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    /// The `userProperties` member.
+    #[serde(rename = "userProperties", default, skip_serializing_if = "Option::is_none")]
+    pub user_properties: Option<Vec<UserProperty>>,
+}
+
+/// The `PublishToIoTCoreResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PublishToIoTCoreResponse {}
+
+/// The `PublishToTopicRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PublishToTopicRequest {
+    /// The `publishMessage` member.
+    #[serde(rename = "publishMessage")]
+    pub publish_message: PublishMessage,
+    /// The `topic` member.
+    #[serde(rename = "topic")]
+    pub topic: String,
+}
+
+/// The `PublishToTopicResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PublishToTopicResponse {}
+
+/// The `PutComponentMetricRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PutComponentMetricRequest {
+    /// The `metrics` member.
+    #[serde(rename = "metrics")]
+    pub metrics: Vec<Metric>,
+}
+
+/// The `PutComponentMetricResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PutComponentMetricResponse {}
+
+/// The `QOS` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QOS {
+    /// `0`.
+    #[serde(rename = "0")]
+    AtMostOnce,
+    /// `1`.
+    #[serde(rename = "1")]
+    AtLeastOnce,
+}
+
+/// The `ReceiveMode` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReceiveMode {
+    /// `RECEIVE_ALL_MESSAGES`.
+    #[serde(rename = "RECEIVE_ALL_MESSAGES")]
+    ReceiveAllMessages,
+    /// `RECEIVE_MESSAGES_FROM_OTHERS`.
+    #[serde(rename = "RECEIVE_MESSAGES_FROM_OTHERS")]
+    ReceiveMessagesFromOthers,
+}
+
+/// The `RequestStatus` enum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestStatus {
+    /// `SUCCEEDED`.
+    #[serde(rename = "SUCCEEDED")]
+    Succeeded,
+    /// `FAILED`.
+    #[serde(rename = "FAILED")]
+    Failed,
+}
+
+/// The `ResourceNotFoundError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResourceNotFoundError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The `resourceName` member.
+    #[serde(rename = "resourceName", default, skip_serializing_if = "Option::is_none")]
+    pub resource_name: Option<String>,
+    /// The `resourceType` member.
+    #[serde(rename = "resourceType", default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+}
+
+impl ModeledError for ResourceNotFoundError {
+    const CODE: ErrorCode = ErrorCode::ResourceNotFoundError;
+}
+
+/// The `RestartComponentRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RestartComponentRequest {
+    /// The `componentName` member.
+    #[serde(rename = "componentName")]
+    pub component_name: String,
+}
+
+/// The `RestartComponentResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RestartComponentResponse {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The `restartStatus` member.
+    #[serde(rename = "restartStatus")]
+    pub restart_status: RequestStatus,
+}
+
+/// The `ResumeComponentRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResumeComponentRequest {
+    /// The `componentName` member.
+    #[serde(rename = "componentName")]
+    pub component_name: String,
+}
+
+/// The `ResumeComponentResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResumeComponentResponse {}
+
+/// The `RunWithInfo` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunWithInfo {
+    /// The `posixUser` member.
+    #[serde(rename = "posixUser", default, skip_serializing_if = "Option::is_none")]
+    pub posix_user: Option<String>,
+    /// The `systemResourceLimits` member.
+    #[serde(rename = "systemResourceLimits", default, skip_serializing_if = "Option::is_none")]
+    pub system_resource_limits: Option<SystemResourceLimits>,
+    /// The `windowsUser` member.
+    #[serde(rename = "windowsUser", default, skip_serializing_if = "Option::is_none")]
+    pub windows_user: Option<String>,
+}
+
+/// The `SecretValue` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SecretValue {
+    /// The `secretBinary` member.
+    #[serde(rename = "secretBinary")]
+    SecretBinary(String),
+    /// The `secretString` member.
+    #[serde(rename = "secretString")]
+    SecretString(String),
+}
+
+/// The `SendConfigurationValidityReportRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SendConfigurationValidityReportRequest {
+    /// The `configurationValidityReport` member.
+    #[serde(rename = "configurationValidityReport")]
+    pub configuration_validity_report: ConfigurationValidityReport,
+}
+
+/// The `SendConfigurationValidityReportResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SendConfigurationValidityReportResponse {}
+
+/// The `ServiceError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ServiceError {
+    /// The `context` member.
+    #[serde(rename = "context", default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<HashMap<String, serde_json::Value>>,
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for ServiceError {
+    const CODE: ErrorCode = ErrorCode::ServiceError;
+}
+
+/// The `StopComponentRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StopComponentRequest {
+    /// The `componentName` member.
+    #[serde(rename = "componentName")]
+    pub component_name: String,
+}
+
+/// The `StopComponentResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StopComponentResponse {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The `stopStatus` member.
+    #[serde(rename = "stopStatus")]
+    pub stop_status: RequestStatus,
+}
+
+/// The `SubscribeToCertificateUpdatesRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToCertificateUpdatesRequest {
+    /// The `certificateOptions` member.
+    #[serde(rename = "certificateOptions")]
+    pub certificate_options: CertificateOptions,
+}
+
+/// The `SubscribeToCertificateUpdatesResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToCertificateUpdatesResponse {}
+
+/// The `SubscribeToConfigurationUpdateRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToConfigurationUpdateRequest {
+    /// The `componentName` member.
+    #[serde(rename = "componentName", default, skip_serializing_if = "Option::is_none")]
+    pub component_name: Option<String>,
+    /// The `keyPath` member.
+    #[serde(rename = "keyPath")]
+    pub key_path: Vec<String>,
+}
+
+/// The `SubscribeToConfigurationUpdateResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToConfigurationUpdateResponse {}
+
+/// The `SubscribeToIoTCoreRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToIoTCoreRequest {
+    /// The `qos` member.
+    #[serde(rename = "qos")]
+    pub qos: QOS,
+    /// The `topicName` member.
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+}
+
+/// The `SubscribeToIoTCoreResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToIoTCoreResponse {}
+
+/// The `SubscribeToTopicRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToTopicRequest {
+    /// The `receiveMode` member.
+    #[serde(rename = "receiveMode", default, skip_serializing_if = "Option::is_none")]
+    pub receive_mode: Option<ReceiveMode>,
+    /// The `topic` member.
+    #[serde(rename = "topic")]
+    pub topic: String,
+}
+
+/// The `SubscribeToTopicResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToTopicResponse {
+    /// The `topicName` member.
+    #[serde(rename = "topicName", default, skip_serializing_if = "Option::is_none")]
+    pub topic_name: Option<String>,
+}
+
+/// The `SubscribeToValidateConfigurationUpdatesRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToValidateConfigurationUpdatesRequest {}
+
+/// The `SubscribeToValidateConfigurationUpdatesResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscribeToValidateConfigurationUpdatesResponse {}
+
+/// The `SubscriptionResponseMessage` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SubscriptionResponseMessage {
+    /// The `binaryMessage` member.
+    #[serde(rename = "binaryMessage")]
+    BinaryMessage(BinaryMessage),
+    /// The `jsonMessage` member.
+    #[serde(rename = "jsonMessage")]
+    JsonMessage(JsonMessage),
+}
+
+/// The `SystemResourceLimits` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SystemResourceLimits {
+    /// The `cpus` member.
+    #[serde(rename = "cpus", default, skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+    /// The `memory` member.
+    #[serde(rename = "memory", default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<i64>,
+}
+
+/// The `UnauthorizedError` error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UnauthorizedError {
+    /// The `message` member.
+    #[serde(rename = "message", default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModeledError for UnauthorizedError {
+    const CODE: ErrorCode = ErrorCode::UnauthorizedError;
+}
+
+/// The `UpdateConfigurationRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpdateConfigurationRequest {
+    /// The `keyPath` member.
+    #[serde(rename = "keyPath", default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<Vec<String>>,
+    /// The `timestamp` member.
+    #[serde(rename = "timestamp")]
+    pub timestamp: f64,
+    /// The `valueToMerge` member.
+    #[serde(rename = "valueToMerge")]
+    pub value_to_merge: HashMap<String, serde_json::Value>,
+}
+
+/// The `UpdateConfigurationResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpdateConfigurationResponse {}
+
+/// The `UpdateThingShadowRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpdateThingShadowRequest {
+    /// The `payload` member.
+    #[serde(rename = "payload")]
+    pub payload: String,
+    /// The `shadowName` member.
+    #[serde(rename = "shadowName", default, skip_serializing_if = "Option::is_none")]
+    pub shadow_name: Option<String>,
+    /// The `thingName` member.
+    #[serde(rename = "thingName")]
+    pub thing_name: String,
+}
+
+/// The `UpdateThingShadowResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpdateThingShadowResponse {
+    /// The `payload` member.
+    #[serde(rename = "payload")]
+    pub payload: String,
+}
+
+/// The `UserProperty` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UserProperty {
+    /// The `key` member.
+    #[serde(rename = "key")]
+    pub key: String,
+    /// The `value` member.
+    #[serde(rename = "value")]
+    pub value: String,
+}
+
+/// The `ValidateAuthorizationTokenRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValidateAuthorizationTokenRequest {
+    /// The `token` member.
+    #[serde(rename = "token")]
+    pub token: String,
+}
+
+/// The `ValidateAuthorizationTokenResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValidateAuthorizationTokenResponse {
+    /// The `isValid` member.
+    #[serde(rename = "isValid")]
+    pub is_valid: bool,
+}
+
+/// The `ValidateConfigurationUpdateEvent` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValidateConfigurationUpdateEvent {
+    /// The `configuration` member.
+    #[serde(rename = "configuration", default, skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<HashMap<String, serde_json::Value>>,
+    /// The `deploymentId` member.
+    #[serde(rename = "deploymentId")]
+    pub deployment_id: String,
+}
+
+/// The `ValidateConfigurationUpdateEvents` union.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ValidateConfigurationUpdateEvents {
+    /// The `validateConfigurationUpdateEvent` member.
+    #[serde(rename = "validateConfigurationUpdateEvent")]
+    ValidateConfigurationUpdateEvent(ValidateConfigurationUpdateEvent),
+}
+
+/// The `VerifyClientDeviceIdentityRequest` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VerifyClientDeviceIdentityRequest {
+    /// The `credential` member.
+    #[serde(rename = "credential")]
+    pub credential: ClientDeviceCredential,
+}
+
+/// The `VerifyClientDeviceIdentityResponse` structure.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VerifyClientDeviceIdentityResponse {
+    /// The `isValidClientDevice` member.
+    #[serde(rename = "isValidClientDevice")]
+    pub is_valid_client_device: bool,
+}
+
+/// The `AuthorizeClientDeviceAction` operation.
+#[derive(Debug)]
+pub struct AuthorizeClientDeviceAction;
+
+impl Operation for AuthorizeClientDeviceAction {
+    const NAME: &'static str = "aws.greengrass#AuthorizeClientDeviceAction";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#AuthorizeClientDeviceActionRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::UnauthorizedError,
+        ErrorCode::ServiceError,
+        ErrorCode::InvalidArgumentsError,
+        ErrorCode::InvalidClientDeviceAuthTokenError,
+    ];
+
+    type Request<'r> = AuthorizeClientDeviceActionRequest;
+    type Response = AuthorizeClientDeviceActionResponse;
+}
+
+/// The `CancelLocalDeployment` operation.
+#[derive(Debug)]
+pub struct CancelLocalDeployment;
+
+impl Operation for CancelLocalDeployment {
+    const NAME: &'static str = "aws.greengrass#CancelLocalDeployment";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#CancelLocalDeploymentRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = CancelLocalDeploymentRequest;
+    type Response = CancelLocalDeploymentResponse;
+}
+
+/// The `CreateDebugPassword` operation.
+#[derive(Debug)]
+pub struct CreateDebugPassword;
+
+impl Operation for CreateDebugPassword {
+    const NAME: &'static str = "aws.greengrass#CreateDebugPassword";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#CreateDebugPasswordRequest";
+    const ERRORS: &'static [ErrorCode] = &[ErrorCode::ServiceError, ErrorCode::UnauthorizedError];
+
+    type Request<'r> = CreateDebugPasswordRequest;
+    type Response = CreateDebugPasswordResponse;
+}
+
+/// The `CreateLocalDeployment` operation.
+#[derive(Debug)]
+pub struct CreateLocalDeployment;
+
+impl Operation for CreateLocalDeployment {
+    const NAME: &'static str = "aws.greengrass#CreateLocalDeployment";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#CreateLocalDeploymentRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::ServiceError,
+        ErrorCode::InvalidRecipeDirectoryPathError,
+        ErrorCode::InvalidArtifactsDirectoryPathError,
+    ];
+
+    type Request<'r> = CreateLocalDeploymentRequest;
+    type Response = CreateLocalDeploymentResponse;
+}
+
+/// The `DeleteThingShadow` operation.
+#[derive(Debug)]
+pub struct DeleteThingShadow;
+
+impl Operation for DeleteThingShadow {
+    const NAME: &'static str = "aws.greengrass#DeleteThingShadow";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#DeleteThingShadowRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::InvalidArgumentsError,
+        ErrorCode::ResourceNotFoundError,
+        ErrorCode::ServiceError,
+        ErrorCode::UnauthorizedError,
+    ];
+
+    type Request<'r> = DeleteThingShadowRequest;
+    type Response = DeleteThingShadowResponse;
+}
+
+/// The `GetClientDeviceAuthToken` operation.
+#[derive(Debug)]
+pub struct GetClientDeviceAuthToken;
+
+impl Operation for GetClientDeviceAuthToken {
+    const NAME: &'static str = "aws.greengrass#GetClientDeviceAuthToken";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#GetClientDeviceAuthTokenRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::UnauthorizedError,
+        ErrorCode::ServiceError,
+        ErrorCode::InvalidArgumentsError,
+        ErrorCode::InvalidCredentialError,
+    ];
+
+    type Request<'r> = GetClientDeviceAuthTokenRequest;
+    type Response = GetClientDeviceAuthTokenResponse;
+}
+
+/// The `GetComponentDetails` operation.
+#[derive(Debug)]
+pub struct GetComponentDetails;
+
+impl Operation for GetComponentDetails {
+    const NAME: &'static str = "aws.greengrass#GetComponentDetails";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#GetComponentDetailsRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::ServiceError,
+        ErrorCode::ResourceNotFoundError,
+        ErrorCode::InvalidArgumentsError,
+    ];
+
+    type Request<'r> = GetComponentDetailsRequest;
+    type Response = GetComponentDetailsResponse;
+}
+
+/// The `GetConfiguration` operation.
+#[derive(Debug)]
+pub struct GetConfiguration;
+
+impl Operation for GetConfiguration {
+    const NAME: &'static str = "aws.greengrass#GetConfiguration";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#GetConfigurationRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = GetConfigurationRequest;
+    type Response = GetConfigurationResponse;
+}
+
+/// The `GetLocalDeploymentStatus` operation.
+#[derive(Debug)]
+pub struct GetLocalDeploymentStatus;
+
+impl Operation for GetLocalDeploymentStatus {
+    const NAME: &'static str = "aws.greengrass#GetLocalDeploymentStatus";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#GetLocalDeploymentStatusRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = GetLocalDeploymentStatusRequest;
+    type Response = GetLocalDeploymentStatusResponse;
+}
+
+/// The `GetSecretValue` operation.
+#[derive(Debug)]
+pub struct GetSecretValue;
+
+impl Operation for GetSecretValue {
+    const NAME: &'static str = "aws.greengrass#GetSecretValue";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#GetSecretValueRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::UnauthorizedError, ErrorCode::ResourceNotFoundError, ErrorCode::ServiceError];
+
+    type Request<'r> = GetSecretValueRequest;
+    type Response = GetSecretValueResponse;
+}
+
+/// The `GetThingShadow` operation.
+#[derive(Debug)]
+pub struct GetThingShadow;
+
+impl Operation for GetThingShadow {
+    const NAME: &'static str = "aws.greengrass#GetThingShadow";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#GetThingShadowRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::InvalidArgumentsError,
+        ErrorCode::ResourceNotFoundError,
+        ErrorCode::ServiceError,
+        ErrorCode::UnauthorizedError,
+    ];
+
+    type Request<'r> = GetThingShadowRequest;
+    type Response = GetThingShadowResponse;
+}
+
+/// The `ListComponents` operation.
+#[derive(Debug)]
+pub struct ListComponents;
+
+impl Operation for ListComponents {
+    const NAME: &'static str = "aws.greengrass#ListComponents";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#ListComponentsRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::InvalidArgumentsError];
+
+    type Request<'r> = ListComponentsRequest;
+    type Response = ListComponentsResponse;
+}
+
+/// The `ListLocalDeployments` operation.
+#[derive(Debug)]
+pub struct ListLocalDeployments;
+
+impl Operation for ListLocalDeployments {
+    const NAME: &'static str = "aws.greengrass#ListLocalDeployments";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#ListLocalDeploymentsRequest";
+    const ERRORS: &'static [ErrorCode] = &[ErrorCode::ServiceError];
+
+    type Request<'r> = ListLocalDeploymentsRequest;
+    type Response = ListLocalDeploymentsResponse;
+}
+
+/// The `ListNamedShadowsForThing` operation.
+#[derive(Debug)]
+pub struct ListNamedShadowsForThing;
+
+impl Operation for ListNamedShadowsForThing {
+    const NAME: &'static str = "aws.greengrass#ListNamedShadowsForThing";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#ListNamedShadowsForThingRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::InvalidArgumentsError,
+        ErrorCode::ResourceNotFoundError,
+        ErrorCode::ServiceError,
+        ErrorCode::UnauthorizedError,
+    ];
+
+    type Request<'r> = ListNamedShadowsForThingRequest;
+    type Response = ListNamedShadowsForThingResponse;
+}
+
+/// The `PauseComponent` operation.
+#[derive(Debug)]
+pub struct PauseComponent;
+
+impl Operation for PauseComponent {
+    const NAME: &'static str = "aws.greengrass#PauseComponent";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#PauseComponentRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::UnauthorizedError, ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = PauseComponentRequest;
+    type Response = PauseComponentResponse;
+}
+
+/// The `PublishToIoTCore` operation.
+#[derive(Debug)]
+pub struct PublishToIoTCore;
+
+impl Operation for PublishToIoTCore {
+    const NAME: &'static str = "aws.greengrass#PublishToIoTCore";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#PublishToIoTCoreRequest";
+    const ERRORS: &'static [ErrorCode] = &[ErrorCode::ServiceError, ErrorCode::UnauthorizedError];
+
+    type Request<'r> = PublishToIoTCoreRequest;
+    type Response = PublishToIoTCoreResponse;
+}
+
+/// The `PublishToTopic` operation.
+#[derive(Debug)]
+pub struct PublishToTopic;
+
+impl Operation for PublishToTopic {
+    const NAME: &'static str = "aws.greengrass#PublishToTopic";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#PublishToTopicRequest";
+    const ERRORS: &'static [ErrorCode] = &[ErrorCode::ServiceError, ErrorCode::UnauthorizedError];
+
+    type Request<'r> = PublishToTopicRequest;
+    type Response = PublishToTopicResponse;
+}
+
+/// The `PutComponentMetric` operation.
+#[derive(Debug)]
+pub struct PutComponentMetric;
+
+impl Operation for PutComponentMetric {
+    const NAME: &'static str = "aws.greengrass#PutComponentMetric";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#PutComponentMetricRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::UnauthorizedError, ErrorCode::ServiceError, ErrorCode::InvalidArgumentsError];
+
+    type Request<'r> = PutComponentMetricRequest;
+    type Response = PutComponentMetricResponse;
+}
+
+/// The `RestartComponent` operation.
+#[derive(Debug)]
+pub struct RestartComponent;
+
+impl Operation for RestartComponent {
+    const NAME: &'static str = "aws.greengrass#RestartComponent";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#RestartComponentRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::UnauthorizedError,
+        ErrorCode::ServiceError,
+        ErrorCode::ComponentNotFoundError,
+        ErrorCode::InvalidArgumentsError,
+    ];
+
+    type Request<'r> = RestartComponentRequest;
+    type Response = RestartComponentResponse;
+}
+
+/// The `ResumeComponent` operation.
+#[derive(Debug)]
+pub struct ResumeComponent;
+
+impl Operation for ResumeComponent {
+    const NAME: &'static str = "aws.greengrass#ResumeComponent";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#ResumeComponentRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::UnauthorizedError, ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = ResumeComponentRequest;
+    type Response = ResumeComponentResponse;
+}
+
+/// The `SendConfigurationValidityReport` operation.
+#[derive(Debug)]
+pub struct SendConfigurationValidityReport;
+
+impl Operation for SendConfigurationValidityReport {
+    const NAME: &'static str = "aws.greengrass#SendConfigurationValidityReport";
+    const REQUEST_MODEL_TYPE: &'static str =
+        "aws.greengrass#SendConfigurationValidityReportRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::InvalidArgumentsError, ErrorCode::ServiceError];
+
+    type Request<'r> = SendConfigurationValidityReportRequest;
+    type Response = SendConfigurationValidityReportResponse;
+}
+
+/// The `StopComponent` operation.
+#[derive(Debug)]
+pub struct StopComponent;
+
+impl Operation for StopComponent {
+    const NAME: &'static str = "aws.greengrass#StopComponent";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#StopComponentRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::UnauthorizedError,
+        ErrorCode::ServiceError,
+        ErrorCode::ComponentNotFoundError,
+        ErrorCode::InvalidArgumentsError,
+    ];
+
+    type Request<'r> = StopComponentRequest;
+    type Response = StopComponentResponse;
+}
+
+/// The `SubscribeToCertificateUpdates` operation.
+#[derive(Debug)]
+pub struct SubscribeToCertificateUpdates;
+
+impl Operation for SubscribeToCertificateUpdates {
+    const NAME: &'static str = "aws.greengrass#SubscribeToCertificateUpdates";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#SubscribeToCertificateUpdatesRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::UnauthorizedError, ErrorCode::InvalidArgumentsError];
+
+    type Request<'r> = SubscribeToCertificateUpdatesRequest;
+    type Response = SubscribeToCertificateUpdatesResponse;
+}
+
+impl StreamingOperation for SubscribeToCertificateUpdates {
+    type Event = CertificateUpdateEvent;
+}
+
+/// The `SubscribeToConfigurationUpdate` operation.
+#[derive(Debug)]
+pub struct SubscribeToConfigurationUpdate;
+
+impl Operation for SubscribeToConfigurationUpdate {
+    const NAME: &'static str = "aws.greengrass#SubscribeToConfigurationUpdate";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#SubscribeToConfigurationUpdateRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = SubscribeToConfigurationUpdateRequest;
+    type Response = SubscribeToConfigurationUpdateResponse;
+}
+
+impl StreamingOperation for SubscribeToConfigurationUpdate {
+    type Event = ConfigurationUpdateEvents;
+}
+
+/// The `SubscribeToIoTCore` operation.
+#[derive(Debug)]
+pub struct SubscribeToIoTCore;
+
+impl Operation for SubscribeToIoTCore {
+    const NAME: &'static str = "aws.greengrass#SubscribeToIoTCore";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#SubscribeToIoTCoreRequest";
+    const ERRORS: &'static [ErrorCode] = &[ErrorCode::ServiceError, ErrorCode::UnauthorizedError];
+
+    type Request<'r> = SubscribeToIoTCoreRequest;
+    type Response = SubscribeToIoTCoreResponse;
+}
+
+impl StreamingOperation for SubscribeToIoTCore {
+    type Event = IoTCoreMessage;
+}
+
+/// The `SubscribeToTopic` operation.
+#[derive(Debug)]
+pub struct SubscribeToTopic;
+
+impl Operation for SubscribeToTopic {
+    const NAME: &'static str = "aws.greengrass#SubscribeToTopic";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#SubscribeToTopicRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::InvalidArgumentsError, ErrorCode::ServiceError, ErrorCode::UnauthorizedError];
+
+    type Request<'r> = SubscribeToTopicRequest;
+    type Response = SubscribeToTopicResponse;
+}
+
+impl StreamingOperation for SubscribeToTopic {
+    type Event = SubscriptionResponseMessage;
+}
+
+/// The `SubscribeToValidateConfigurationUpdates` operation.
+#[derive(Debug)]
+pub struct SubscribeToValidateConfigurationUpdates;
+
+impl Operation for SubscribeToValidateConfigurationUpdates {
+    const NAME: &'static str = "aws.greengrass#SubscribeToValidateConfigurationUpdates";
+    const REQUEST_MODEL_TYPE: &'static str =
+        "aws.greengrass#SubscribeToValidateConfigurationUpdatesRequest";
+    const ERRORS: &'static [ErrorCode] = &[ErrorCode::ServiceError];
+
+    type Request<'r> = SubscribeToValidateConfigurationUpdatesRequest;
+    type Response = SubscribeToValidateConfigurationUpdatesResponse;
+}
+
+impl StreamingOperation for SubscribeToValidateConfigurationUpdates {
+    type Event = ValidateConfigurationUpdateEvents;
+}
+
+/// The `UpdateConfiguration` operation.
+#[derive(Debug)]
+pub struct UpdateConfiguration;
+
+impl Operation for UpdateConfiguration {
+    const NAME: &'static str = "aws.greengrass#UpdateConfiguration";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#UpdateConfigurationRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::ServiceError,
+        ErrorCode::UnauthorizedError,
+        ErrorCode::ConflictError,
+        ErrorCode::FailedUpdateConditionCheckError,
+        ErrorCode::InvalidArgumentsError,
+    ];
+
+    type Request<'r> = UpdateConfigurationRequest;
+    type Response = UpdateConfigurationResponse;
+}
+
+/// The `UpdateThingShadow` operation.
+#[derive(Debug)]
+pub struct UpdateThingShadow;
+
+impl Operation for UpdateThingShadow {
+    const NAME: &'static str = "aws.greengrass#UpdateThingShadow";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#UpdateThingShadowRequest";
+    const ERRORS: &'static [ErrorCode] = &[
+        ErrorCode::InvalidArgumentsError,
+        ErrorCode::ConflictError,
+        ErrorCode::ServiceError,
+        ErrorCode::UnauthorizedError,
+    ];
+
+    type Request<'r> = UpdateThingShadowRequest;
+    type Response = UpdateThingShadowResponse;
+}
+
+/// The `ValidateAuthorizationToken` operation.
+#[derive(Debug)]
+pub struct ValidateAuthorizationToken;
+
+impl Operation for ValidateAuthorizationToken {
+    const NAME: &'static str = "aws.greengrass#ValidateAuthorizationToken";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#ValidateAuthorizationTokenRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::InvalidTokenError, ErrorCode::UnauthorizedError, ErrorCode::ServiceError];
+
+    type Request<'r> = ValidateAuthorizationTokenRequest;
+    type Response = ValidateAuthorizationTokenResponse;
+}
+
+/// The `VerifyClientDeviceIdentity` operation.
+#[derive(Debug)]
+pub struct VerifyClientDeviceIdentity;
+
+impl Operation for VerifyClientDeviceIdentity {
+    const NAME: &'static str = "aws.greengrass#VerifyClientDeviceIdentity";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#VerifyClientDeviceIdentityRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::UnauthorizedError, ErrorCode::ServiceError, ErrorCode::InvalidArgumentsError];
+
+    type Request<'r> = VerifyClientDeviceIdentityRequest;
+    type Response = VerifyClientDeviceIdentityResponse;
+}
+
+impl Connection {
+    /// Invokes the [`AuthorizeClientDeviceAction`] operation.
+    pub async fn authorize_client_device_action(
+        &self,
+        request: AuthorizeClientDeviceActionRequest,
+    ) -> Result<AuthorizeClientDeviceActionResponse> {
+        self.invoke::<AuthorizeClientDeviceAction>(request).await
+    }
+
+    /// Invokes the [`CancelLocalDeployment`] operation.
+    pub async fn cancel_local_deployment(
+        &self,
+        request: CancelLocalDeploymentRequest,
+    ) -> Result<CancelLocalDeploymentResponse> {
+        self.invoke::<CancelLocalDeployment>(request).await
+    }
+
+    /// Invokes the [`CreateDebugPassword`] operation.
+    pub async fn create_debug_password(
+        &self,
+        request: CreateDebugPasswordRequest,
+    ) -> Result<CreateDebugPasswordResponse> {
+        self.invoke::<CreateDebugPassword>(request).await
+    }
+
+    /// Invokes the [`CreateLocalDeployment`] operation.
+    pub async fn create_local_deployment(
+        &self,
+        request: CreateLocalDeploymentRequest,
+    ) -> Result<CreateLocalDeploymentResponse> {
+        self.invoke::<CreateLocalDeployment>(request).await
+    }
+
+    /// Invokes the [`DeleteThingShadow`] operation.
+    pub async fn delete_thing_shadow(
+        &self,
+        request: DeleteThingShadowRequest,
+    ) -> Result<DeleteThingShadowResponse> {
+        self.invoke::<DeleteThingShadow>(request).await
+    }
+
+    /// Invokes the [`GetClientDeviceAuthToken`] operation.
+    pub async fn get_client_device_auth_token(
+        &self,
+        request: GetClientDeviceAuthTokenRequest,
+    ) -> Result<GetClientDeviceAuthTokenResponse> {
+        self.invoke::<GetClientDeviceAuthToken>(request).await
+    }
+
+    /// Invokes the [`GetComponentDetails`] operation.
+    pub async fn get_component_details(
+        &self,
+        request: GetComponentDetailsRequest,
+    ) -> Result<GetComponentDetailsResponse> {
+        self.invoke::<GetComponentDetails>(request).await
+    }
+
+    /// Invokes the [`GetConfiguration`] operation.
+    pub async fn get_configuration(
+        &self,
+        request: GetConfigurationRequest,
+    ) -> Result<GetConfigurationResponse> {
+        self.invoke::<GetConfiguration>(request).await
+    }
+
+    /// Invokes the [`GetLocalDeploymentStatus`] operation.
+    pub async fn get_local_deployment_status(
+        &self,
+        request: GetLocalDeploymentStatusRequest,
+    ) -> Result<GetLocalDeploymentStatusResponse> {
+        self.invoke::<GetLocalDeploymentStatus>(request).await
+    }
+
+    /// Invokes the [`GetSecretValue`] operation.
+    pub async fn get_secret_value(
+        &self,
+        request: GetSecretValueRequest,
+    ) -> Result<GetSecretValueResponse> {
+        self.invoke::<GetSecretValue>(request).await
+    }
+
+    /// Invokes the [`GetThingShadow`] operation.
+    pub async fn get_thing_shadow(
+        &self,
+        request: GetThingShadowRequest,
+    ) -> Result<GetThingShadowResponse> {
+        self.invoke::<GetThingShadow>(request).await
+    }
+
+    /// Invokes the [`ListComponents`] operation.
+    pub async fn list_components(
+        &self,
+        request: ListComponentsRequest,
+    ) -> Result<ListComponentsResponse> {
+        self.invoke::<ListComponents>(request).await
+    }
+
+    /// Invokes the [`ListLocalDeployments`] operation.
+    pub async fn list_local_deployments(
+        &self,
+        request: ListLocalDeploymentsRequest,
+    ) -> Result<ListLocalDeploymentsResponse> {
+        self.invoke::<ListLocalDeployments>(request).await
+    }
+
+    /// Invokes the [`ListNamedShadowsForThing`] operation.
+    pub async fn list_named_shadows_for_thing(
+        &self,
+        request: ListNamedShadowsForThingRequest,
+    ) -> Result<ListNamedShadowsForThingResponse> {
+        self.invoke::<ListNamedShadowsForThing>(request).await
+    }
+
+    /// Invokes the [`PauseComponent`] operation.
+    pub async fn pause_component(
+        &self,
+        request: PauseComponentRequest,
+    ) -> Result<PauseComponentResponse> {
+        self.invoke::<PauseComponent>(request).await
+    }
+
+    /// Invokes the [`PublishToIoTCore`] operation.
+    pub async fn publish_to_iot_core(
+        &self,
+        request: PublishToIoTCoreRequest,
+    ) -> Result<PublishToIoTCoreResponse> {
+        self.invoke::<PublishToIoTCore>(request).await
+    }
+
+    /// Invokes the [`PublishToTopic`] operation.
+    pub async fn publish_to_topic(
+        &self,
+        request: PublishToTopicRequest,
+    ) -> Result<PublishToTopicResponse> {
+        self.invoke::<PublishToTopic>(request).await
+    }
+
+    /// Invokes the [`PutComponentMetric`] operation.
+    pub async fn put_component_metric(
+        &self,
+        request: PutComponentMetricRequest,
+    ) -> Result<PutComponentMetricResponse> {
+        self.invoke::<PutComponentMetric>(request).await
+    }
+
+    /// Invokes the [`RestartComponent`] operation.
+    pub async fn restart_component(
+        &self,
+        request: RestartComponentRequest,
+    ) -> Result<RestartComponentResponse> {
+        self.invoke::<RestartComponent>(request).await
+    }
+
+    /// Invokes the [`ResumeComponent`] operation.
+    pub async fn resume_component(
+        &self,
+        request: ResumeComponentRequest,
+    ) -> Result<ResumeComponentResponse> {
+        self.invoke::<ResumeComponent>(request).await
+    }
+
+    /// Invokes the [`SendConfigurationValidityReport`] operation.
+    pub async fn send_configuration_validity_report(
+        &self,
+        request: SendConfigurationValidityReportRequest,
+    ) -> Result<SendConfigurationValidityReportResponse> {
+        self.invoke::<SendConfigurationValidityReport>(request).await
+    }
+
+    /// Invokes the [`StopComponent`] operation.
+    pub async fn stop_component(
+        &self,
+        request: StopComponentRequest,
+    ) -> Result<StopComponentResponse> {
+        self.invoke::<StopComponent>(request).await
+    }
+
+    /// Subscribes to the events of the [`SubscribeToCertificateUpdates`] operation.
+    pub async fn subscribe_to_certificate_updates(
+        &self,
+        request: SubscribeToCertificateUpdatesRequest,
+    ) -> Result<Subscription<CertificateUpdateEvent>> {
+        self.subscribe::<SubscribeToCertificateUpdates>(request).await
+    }
+
+    /// Subscribes to the events of the [`SubscribeToConfigurationUpdate`] operation.
+    pub async fn subscribe_to_configuration_update(
+        &self,
+        request: SubscribeToConfigurationUpdateRequest,
+    ) -> Result<Subscription<ConfigurationUpdateEvents>> {
+        self.subscribe::<SubscribeToConfigurationUpdate>(request).await
+    }
+
+    /// Subscribes to the events of the [`SubscribeToIoTCore`] operation.
+    pub async fn subscribe_to_iot_core(
+        &self,
+        request: SubscribeToIoTCoreRequest,
+    ) -> Result<Subscription<IoTCoreMessage>> {
+        self.subscribe::<SubscribeToIoTCore>(request).await
+    }
+
+    /// Subscribes to the events of the [`SubscribeToTopic`] operation.
+    pub async fn subscribe_to_topic(
+        &self,
+        request: SubscribeToTopicRequest,
+    ) -> Result<Subscription<SubscriptionResponseMessage>> {
+        self.subscribe::<SubscribeToTopic>(request).await
+    }
+
+    /// Subscribes to the events of the [`SubscribeToValidateConfigurationUpdates`] operation.
+    pub async fn subscribe_to_validate_configuration_updates(
+        &self,
+        request: SubscribeToValidateConfigurationUpdatesRequest,
+    ) -> Result<Subscription<ValidateConfigurationUpdateEvents>> {
+        self.subscribe::<SubscribeToValidateConfigurationUpdates>(request).await
+    }
+
+    /// Invokes the [`UpdateConfiguration`] operation.
+    pub async fn update_configuration(
+        &self,
+        request: UpdateConfigurationRequest,
+    ) -> Result<UpdateConfigurationResponse> {
+        self.invoke::<UpdateConfiguration>(request).await
+    }
+
+    /// Invokes the [`UpdateThingShadow`] operation.
+    pub async fn update_thing_shadow(
+        &self,
+        request: UpdateThingShadowRequest,
+    ) -> Result<UpdateThingShadowResponse> {
+        self.invoke::<UpdateThingShadow>(request).await
+    }
+
+    /// Invokes the [`ValidateAuthorizationToken`] operation.
+    pub async fn validate_authorization_token(
+        &self,
+        request: ValidateAuthorizationTokenRequest,
+    ) -> Result<ValidateAuthorizationTokenResponse> {
+        self.invoke::<ValidateAuthorizationToken>(request).await
+    }
+
+    /// Invokes the [`VerifyClientDeviceIdentity`] operation.
+    pub async fn verify_client_device_identity(
+        &self,
+        request: VerifyClientDeviceIdentityRequest,
+    ) -> Result<VerifyClientDeviceIdentityResponse> {
+        self.invoke::<VerifyClientDeviceIdentity>(request).await
+    }
+}
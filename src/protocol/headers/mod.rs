@@ -1,8 +1,13 @@
 //! Headers for IPC messages.
 
+use core::ops::Range;
 use endi::{ReadBytes, WriteBytes};
 use enumflags2::{BitFlag, BitFlags};
-use std::{borrow::Cow, collections::HashMap, io::Write};
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap},
+    io::Write,
+};
 
 use crate::{Error, Result};
 
@@ -14,12 +19,20 @@ mod message_type;
 pub use message_type::MessageType;
 
 /// Headers for IPC messages.
+///
+/// The headers keep their order, so a message always encodes to the same bytes. Headers created
+/// through [`Headers::new`] start with the mandatory `:message-type`, `:message-flags` and
+/// `:stream-id` headers, followed by the other headers in insertion order. Decoded headers keep
+/// the order they had on the wire.
+///
+/// As in eventstream, a header name appears only once: setting a header that already exists
+/// replaces its value in place, and if a name is repeated on the wire, the last value wins.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Headers<'h> {
     stream_id: i32,
     message_type: MessageType,
     message_flags: BitFlags<MessageFlags>,
-    headers: HashMap<Cow<'h, str>, Value<'h>>,
+    headers: Vec<(Cow<'h, str>, Value<'h>)>,
 }
 
 impl<'h> Headers<'h> {
@@ -29,28 +42,105 @@ impl<'h> Headers<'h> {
         message_type: MessageType,
         message_flags: BitFlags<MessageFlags>,
     ) -> Self {
-        let mut headers = HashMap::new();
-        headers.insert(":stream-id".into(), Value::Int32(stream_id));
-        headers.insert(":message-type".into(), Value::Int32(message_type.into()));
-        headers.insert(":message-flags".into(), Value::Int32(message_flags.bits() as i32));
+        let headers = vec![
+            (":message-type".into(), Value::Int32(message_type.into())),
+            (":message-flags".into(), Value::Int32(message_flags.bits() as i32)),
+            (":stream-id".into(), Value::Int32(stream_id)),
+        ];
 
         Self { headers, stream_id, message_type, message_flags }
     }
 
     /// Insert a new header into the set.
-    pub fn insert<N>(&mut self, name: N, value: Value<'h>)
+    ///
+    /// If a header with the same name already exists, its value is replaced in place. However,
+    /// existing headers with reserved names (starting with `:`) can not be replaced this way, use
+    /// the specific setters (e.g. [`Headers::set_content_type`]) for that. Fails if the name is
+    /// longer than 255 bytes.
+    ///
+    /// The value can be anything convertible into a [`Value`], e.g. a `&str`, an `i32` or a
+    /// [`std::time::SystemTime`] for a timestamp.
+    pub fn insert<N, V>(&mut self, name: N, value: V) -> Result<()>
     where
         N: Into<Cow<'h, str>>,
+        V: Into<Value<'h>>,
     {
-        self.headers.insert(name.into(), value);
+        let name = name.into();
+        if name.len() > MAX_NAME_LEN {
+            return Err(Error::InvalidHeader(format!(
+                "name `{name}` is longer than {MAX_NAME_LEN} bytes"
+            )));
+        }
+        if name.starts_with(':') && self.get(&name).is_some() {
+            return Err(Error::InvalidHeader(format!("reserved header `{name}` is already set")));
+        }
+        insert(&mut self.headers, name, value.into());
+
+        Ok(())
     }
 
     /// Get a header by name.
-    pub fn get(&self, name: &'static str) -> Option<&Value<'_>> {
-        self.headers.get(name)
+    pub fn get(&self, name: &str) -> Option<&Value<'h>> {
+        self.headers.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// The content type of the payload, from the `:content-type` header.
+    pub fn content_type(&self) -> Option<&str> {
+        self.get(CONTENT_TYPE).and_then(Value::as_str)
+    }
+
+    /// Set the content type of the payload, in the `:content-type` header.
+    pub fn set_content_type<V>(&mut self, content_type: V)
+    where
+        V: Into<Cow<'h, str>>,
+    {
+        insert(&mut self.headers, CONTENT_TYPE.into(), Value::String(content_type.into()));
+    }
+
+    /// The protocol version, from the `:version` header.
+    pub fn version(&self) -> Option<&str> {
+        self.get(VERSION).and_then(Value::as_str)
+    }
+
+    /// Set the protocol version, in the `:version` header.
+    pub fn set_version<V>(&mut self, version: V)
+    where
+        V: Into<Cow<'h, str>>,
+    {
+        insert(&mut self.headers, VERSION.into(), Value::String(version.into()));
+    }
+
+    /// The model type of the payload, from the `service-model-type` header.
+    pub fn service_model_type(&self) -> Option<&str> {
+        self.get(SERVICE_MODEL_TYPE).and_then(Value::as_str)
+    }
+
+    /// Set the model type of the payload, in the `service-model-type` header.
+    pub fn set_service_model_type<V>(&mut self, service_model_type: V)
+    where
+        V: Into<Cow<'h, str>>,
+    {
+        insert(
+            &mut self.headers,
+            SERVICE_MODEL_TYPE.into(),
+            Value::String(service_model_type.into()),
+        );
+    }
+
+    /// The operation called, from the `operation` header.
+    pub fn operation(&self) -> Option<&str> {
+        self.get(OPERATION).and_then(Value::as_str)
+    }
+
+    /// Set the operation called, in the `operation` header.
+    pub fn set_operation<V>(&mut self, operation: V)
+    where
+        V: Into<Cow<'h, str>>,
+    {
+        insert(&mut self.headers, OPERATION.into(), Value::String(operation.into()));
     }
 
-    /// Iterate over the headers.
+    /// Iterate over the headers, in order.
     pub fn iter(&self) -> impl Iterator<Item = (&str, &Value<'_>)> {
         self.headers.iter().map(|(k, v)| (k.as_ref(), v))
     }
@@ -70,38 +160,91 @@ impl<'h> Headers<'h> {
 
     /// The size in bytes of the headers in the IPC wire format.
     pub fn size_in_bytes(&self) -> Result<u32> {
-        self.headers.iter().try_fold(0, |acc, (name, value)| {
-            name.len()
-                .try_into()
-                .map_err(|_| Error::Protocol("Header name too long".into()))
-                .map(|len: u32| acc + 1 + len)
-                .and_then(|len| value.size_in_bytes().map(|v_len| len + v_len))
+        self.headers.iter().try_fold(0u32, |acc, (name, value)| {
+            acc.checked_add(header_size_in_bytes(name, value)?)
+                .ok_or_else(|| Error::Protocol("Headers too large".into()))
         })
     }
 
     /// Read headers from the given bytes in the IPC wire format.
     pub fn from_bytes(bytes: &mut &'h [u8]) -> Result<Self> {
-        let mut headers = HashMap::new();
+        Self::decode(bytes, None)
+    }
+
+    /// Like [`Headers::from_bytes`], but also records the range of the bytes taken by each header,
+    /// for [`Headers::from_ranges`].
+    pub(crate) fn from_bytes_with_ranges(
+        bytes: &mut &'h [u8],
+        ranges: &mut Vec<Range<usize>>,
+    ) -> Result<Self> {
+        Self::decode(bytes, Some(ranges))
+    }
+
+    /// Rebuild headers decoded by [`Headers::from_bytes_with_ranges`] from the same bytes.
+    ///
+    /// Only the headers at the given ranges are read, without resolving repeated names or checking
+    /// for the mandatory headers again.
+    pub(crate) fn from_ranges(
+        bytes: &'h [u8],
+        ranges: &[Range<usize>],
+        stream_id: i32,
+        message_type: MessageType,
+        message_flags: BitFlags<MessageFlags>,
+    ) -> Result<Self> {
+        let headers = ranges
+            .iter()
+            .map(|range| {
+                let mut header = bytes
+                    .get(range.clone())
+                    .ok_or(Error::Protocol("Invalid header range".into()))?;
+                let name = read_header_name_from_bytes(&mut header)?;
+
+                Ok((Cow::Borrowed(name), Value::from_bytes(&mut header)?))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { headers, stream_id, message_type, message_flags })
+    }
+
+    fn decode(bytes: &mut &'h [u8], mut ranges: Option<&mut Vec<Range<usize>>>) -> Result<Self> {
+        let len = bytes.len();
+        let mut headers = Vec::new();
+        // The position of each name in `headers`, so repeated names are resolved in linear time.
+        let mut positions = HashMap::new();
 
         while !bytes.is_empty() {
+            let start = len - bytes.len();
             let name = read_header_name_from_bytes(bytes)?;
             let value = Value::from_bytes(bytes)?;
-
-            headers.insert(Cow::Borrowed(name), value);
+            let range = start..len - bytes.len();
+
+            match positions.entry(name) {
+                Entry::Vacant(position) => {
+                    position.insert(headers.len());
+                    headers.push((Cow::Borrowed(name), value));
+                    if let Some(ranges) = ranges.as_deref_mut() {
+                        ranges.push(range);
+                    }
+                }
+                Entry::Occupied(position) => {
+                    headers[*position.get()].1 = value;
+                    if let Some(ranges) = ranges.as_deref_mut() {
+                        ranges[*position.get()] = range;
+                    }
+                }
+            }
         }
+        let get = |name| positions.get(name).map(|&i| &headers[i].1);
 
         // Ensure all mandatory headers are present.
-        let stream_id = headers
-            .get(":stream-id")
+        let stream_id = get(":stream-id")
             .and_then(Value::as_int32)
             .ok_or(Error::MissingHeader(":stream-id"))?;
-        let message_type = headers
-            .get(":message-type")
+        let message_type = get(":message-type")
             .and_then(Value::as_int32)
             .ok_or(Error::MissingHeader(":message-type"))
             .and_then(TryInto::try_into)?;
-        let message_flags = headers
-            .get(":message-flags")
+        let message_flags = get(":message-flags")
             .and_then(Value::as_int32)
             .ok_or(Error::MissingHeader(":message-flags"))
             .and_then(|i| {
@@ -140,7 +283,38 @@ impl<'h> Headers<'h> {
     }
 }
 
-fn write_header_as_bytes(name: &str, value: &Value<'_>, writer: &mut impl Write) -> Result<usize> {
+pub(crate) const CONTENT_TYPE: &str = ":content-type";
+const VERSION: &str = ":version";
+const SERVICE_MODEL_TYPE: &str = "service-model-type";
+const OPERATION: &str = "operation";
+const MAX_NAME_LEN: usize = u8::MAX as usize;
+
+/// Insert a header, replacing the value of any existing header with the same name in place.
+fn insert<'h>(headers: &mut Vec<(Cow<'h, str>, Value<'h>)>, name: Cow<'h, str>, value: Value<'h>) {
+    match headers.iter_mut().find(|(n, _)| *n == name) {
+        Some((_, v)) => *v = value,
+        None => headers.push((name, value)),
+    }
+}
+
+/// The size in bytes of a single header in the IPC wire format.
+pub(crate) fn header_size_in_bytes(name: &str, value: &Value<'_>) -> Result<u32> {
+    let name_len: u32 =
+        name.len().try_into().map_err(|_| Error::Protocol("Header name too long".into()))?;
+    let value_len = value.size_in_bytes()?;
+
+    name_len
+        .checked_add(1)
+        .and_then(|len| len.checked_add(value_len))
+        .ok_or_else(|| Error::Protocol("Headers too large".into()))
+}
+
+/// Write a single header in the IPC wire format.
+pub(crate) fn write_header_as_bytes(
+    name: &str,
+    value: &Value<'_>,
+    writer: &mut impl Write,
+) -> Result<usize> {
     let mut bytes_written = 0;
 
     bytes_written += write_header_name_as_bytes(name, writer)?;
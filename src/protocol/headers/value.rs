@@ -1,5 +1,13 @@
+use core::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
 use endi::{ReadBytes, WriteBytes};
-use std::{borrow::Cow, io::Write};
+use std::{
+    borrow::Cow,
+    io::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use crate::{Error, Result};
 
@@ -20,7 +28,10 @@ pub enum Value<'v> {
     ByteBuffer(Cow<'v, [u8]>),
     /// A string value.
     String(Cow<'v, str>),
-    /// A timestamp value.
+    /// A timestamp value, in milliseconds since the Unix epoch.
+    ///
+    /// Convert it from and to a [`SystemTime`] through `From` and `TryFrom`, or
+    /// [`Value::as_system_time`].
     Timestamp(i64),
     /// A UUID value.
     Uuid(uuid::Uuid),
@@ -112,7 +123,7 @@ impl Value<'_> {
     pub fn size_in_bytes(&self) -> Result<u32> {
         // All values have a type byte so that's why 5 bytes for i32 for example.
         Ok(match self {
-            Value::Bool(_) => 0,
+            Value::Bool(_) => 1,
             Value::Byte(_) => 2,
             Value::Int16(_) => 3,
             Value::Int32(_) => 5,
@@ -210,6 +221,17 @@ impl Value<'_> {
         }
     }
 
+    /// Get the value as a point in time if it is a timestamp that can be represented as one.
+    pub fn as_system_time(&self) -> Option<SystemTime> {
+        let millis = self.as_timestamp()?;
+        let duration = Duration::from_millis(millis.unsigned_abs());
+        if millis >= 0 {
+            UNIX_EPOCH.checked_add(duration)
+        } else {
+            UNIX_EPOCH.checked_sub(duration)
+        }
+    }
+
     /// Get the value as a UUID if it is one.
     pub fn as_uuid(&self) -> Option<uuid::Uuid> {
         match self {
@@ -219,6 +241,23 @@ impl Value<'_> {
     }
 }
 
+impl Value<'_> {
+    /// The name of the type of the value, for error messages.
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "boolean",
+            Value::Byte(_) => "byte",
+            Value::Int16(_) => "int16",
+            Value::Int32(_) => "int32",
+            Value::Int64(_) => "int64",
+            Value::ByteBuffer(_) => "byte buffer",
+            Value::String(_) => "string",
+            Value::Timestamp(_) => "timestamp",
+            Value::Uuid(_) => "UUID",
+        }
+    }
+}
+
 impl<'v> Value<'v> {
     /// Read a header value from the given bytes in the IPC wire format.
     pub fn from_bytes(bytes: &mut &'v [u8]) -> Result<Self> {
@@ -295,3 +334,192 @@ impl<'v> Value<'v> {
         }
     }
 }
+
+impl Display for Value<'_> {
+    /// Formats the value in a human-readable way: byte buffers in hex and timestamps in RFC 3339.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Byte(b) => write!(f, "{b}"),
+            Value::Int16(i) => write!(f, "{i}"),
+            Value::Int32(i) => write!(f, "{i}"),
+            Value::Int64(i) => write!(f, "{i}"),
+            Value::ByteBuffer(bytes) => {
+                f.write_str("0x")?;
+                bytes.iter().try_for_each(|b| write!(f, "{b:02x}"))
+            }
+            Value::String(s) => f.write_str(s),
+            Value::Timestamp(millis) => write_rfc3339(f, *millis),
+            Value::Uuid(uuid) => write!(f, "{uuid}"),
+        }
+    }
+}
+
+/// Write a timestamp in milliseconds since the Unix epoch as an RFC 3339 date and time in UTC.
+fn write_rfc3339(f: &mut Formatter<'_>, millis: i64) -> fmt::Result {
+    let secs = millis.div_euclid(1000);
+    let (days, secs_of_day) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Howard Hinnant's `civil_from_days` algorithm.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    write!(
+        f,
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        millis.rem_euclid(1000),
+    )
+}
+
+macro_rules! impl_from {
+    ($($type:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl<'v> From<$type> for Value<'v> {
+                fn from(value: $type) -> Self {
+                    Value::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    bool => Bool,
+    u8 => Byte,
+    i16 => Int16,
+    i32 => Int32,
+    i64 => Int64,
+    &'v [u8] => ByteBuffer,
+    Vec<u8> => ByteBuffer,
+    Cow<'v, [u8]> => ByteBuffer,
+    &'v str => String,
+    String => String,
+    Cow<'v, str> => String,
+    uuid::Uuid => Uuid,
+}
+
+impl From<SystemTime> for Value<'_> {
+    /// Creates a timestamp, truncated to millisecond precision.
+    ///
+    /// Times too far from the Unix epoch to be represented are clamped.
+    fn from(time: SystemTime) -> Self {
+        let millis = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => i64::try_from(since.as_millis()).unwrap_or(i64::MAX),
+            Err(e) => {
+                // Round down, so the timestamp isn't later than the actual time.
+                let before = e.duration();
+                let millis =
+                    before.as_millis() + u128::from(before.subsec_nanos() % 1_000_000 != 0);
+                i64::try_from(millis).map_or(i64::MIN, |millis| -millis)
+            }
+        };
+
+        Value::Timestamp(millis)
+    }
+}
+
+macro_rules! impl_try_from {
+    ($($type:ty => $getter:ident, $expected:literal),* $(,)?) => {
+        $(
+            impl<'a> TryFrom<&'a Value<'_>> for $type {
+                type Error = Error;
+
+                fn try_from(value: &'a Value<'_>) -> Result<Self> {
+                    value.$getter().ok_or_else(|| mismatch(value, $expected))
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from! {
+    bool => as_bool, "boolean",
+    u8 => as_byte, "byte",
+    i16 => as_int16, "int16",
+    i32 => as_int32, "int32",
+    i64 => as_int64, "int64",
+    &'a [u8] => as_byte_buffer, "byte buffer",
+    &'a str => as_str, "string",
+    uuid::Uuid => as_uuid, "UUID",
+    SystemTime => as_system_time, "timestamp",
+}
+
+fn mismatch(value: &Value<'_>, expected: &str) -> Error {
+    match value {
+        // The right type, but out of range.
+        Value::Timestamp(millis) if expected == "timestamp" => {
+            Error::InvalidHeader(format!("timestamp {millis} is out of range"))
+        }
+        _ => Error::InvalidHeader(format!("expected {expected}, got {}", value.type_name())),
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use chrono::{DateTime, Utc};
+
+    use super::{mismatch, Value};
+    use crate::{Error, Result};
+
+    impl From<DateTime<Utc>> for Value<'_> {
+        /// Creates a timestamp, truncated to millisecond precision.
+        fn from(time: DateTime<Utc>) -> Self {
+            Value::Timestamp(time.timestamp_millis())
+        }
+    }
+
+    impl TryFrom<&Value<'_>> for DateTime<Utc> {
+        type Error = Error;
+
+        fn try_from(value: &Value<'_>) -> Result<Self> {
+            value
+                .as_timestamp()
+                .and_then(DateTime::from_timestamp_millis)
+                .ok_or_else(|| mismatch(value, "timestamp"))
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use time::OffsetDateTime;
+
+    use super::{mismatch, Value};
+    use crate::{Error, Result};
+
+    impl From<OffsetDateTime> for Value<'_> {
+        /// Creates a timestamp, truncated to millisecond precision.
+        ///
+        /// The time is converted to UTC.
+        fn from(time: OffsetDateTime) -> Self {
+            // Always in range, even with the ±999,999 years of the `large-dates` feature of `time`.
+            let millis = time.unix_timestamp_nanos().div_euclid(1_000_000);
+
+            Value::Timestamp(millis as i64)
+        }
+    }
+
+    impl TryFrom<&Value<'_>> for OffsetDateTime {
+        type Error = Error;
+
+        fn try_from(value: &Value<'_>) -> Result<Self> {
+            value
+                .as_timestamp()
+                .and_then(|millis| {
+                    OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000).ok()
+                })
+                .ok_or_else(|| mismatch(value, "timestamp"))
+        }
+    }
+}
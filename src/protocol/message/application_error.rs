@@ -0,0 +1,221 @@
+use core::fmt::{self, Display, Formatter};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::protocol::Headers;
+
+/// The payload of an `ApplicationError` message: an error returned by a Greengrass service.
+///
+/// This is what [`crate::Error::Application`] carries. Use [`ApplicationError::code`] to branch on
+/// the kind of error, e.g. authorization failures or missing resources, and
+/// [`ApplicationError::downcast`] to get at its modeled fields.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApplicationError {
+    #[serde(rename = "_errorCode", default, skip_serializing_if = "Option::is_none")]
+    code: Option<ErrorCode>,
+    #[serde(rename = "_message", default, skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(rename = "_service", default, skip_serializing_if = "Option::is_none")]
+    service: Option<String>,
+    #[serde(flatten)]
+    details: Map<String, Value>,
+}
+
+impl ApplicationError {
+    /// Creates a new `ApplicationError`.
+    pub fn new(code: ErrorCode, message: Option<String>, service: Option<String>) -> Self {
+        Self { code: Some(code), message, service, details: Map::new() }
+    }
+
+    /// The kind of error.
+    pub fn code(&self) -> &ErrorCode {
+        // Only `None` if the error couldn't be identified at all.
+        self.code.as_ref().unwrap_or(&ErrorCode::ServiceError)
+    }
+
+    /// The human-readable error message, if any.
+    ///
+    /// Falls back to the modeled `message` field for servers that only send that one.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref().or_else(|| self.details.get("message").and_then(Value::as_str))
+    }
+
+    /// The service that returned the error, if known.
+    pub fn service(&self) -> Option<&str> {
+        self.service.as_deref()
+    }
+
+    /// Any other fields of the error, e.g. `resourceType` and `resourceName` of a
+    /// [`ErrorCode::ResourceNotFoundError`].
+    pub fn details(&self) -> &Map<String, Value> {
+        &self.details
+    }
+
+    /// The error as its modeled structure, e.g.
+    /// [`crate::protocol::generated::ResourceNotFoundError`].
+    ///
+    /// Returns `None` if the error is of another kind or its fields don't match the model.
+    pub fn downcast<E: ModeledError>(&self) -> Option<E> {
+        if *self.code() != E::CODE {
+            return None;
+        }
+
+        let mut fields = self.details.clone();
+        if let Some(message) = &self.message {
+            fields.entry("message").or_insert_with(|| message.clone().into());
+        }
+
+        serde_json::from_value(Value::Object(fields)).ok()
+    }
+
+    /// Parse the error from the headers and payload of an `ApplicationError` message.
+    ///
+    /// Payloads that aren't the expected JSON object are kept as the error message. If the
+    /// payload doesn't name the error, it's taken from the `service-model-type` header.
+    pub(crate) fn from_message(headers: &Headers<'_>, payload: &[u8]) -> Self {
+        let mut error = match serde_json::from_slice::<Self>(payload) {
+            Ok(error) => error,
+            Err(_) => {
+                let message = match serde_json::from_slice::<String>(payload) {
+                    Ok(message) => message,
+                    Err(_) => String::from_utf8_lossy(payload).into_owned(),
+                };
+                Self {
+                    code: None,
+                    message: (!message.is_empty()).then_some(message),
+                    service: None,
+                    details: Map::new(),
+                }
+            }
+        };
+        if error.code.is_none() {
+            error.code = headers
+                .service_model_type()
+                .map(|model_type| model_type.rsplit('#').next().unwrap_or(model_type).into());
+        }
+
+        error
+    }
+}
+
+impl Display for ApplicationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())?;
+        if let Some(service) = &self.service {
+            write!(f, " from `{service}`")?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl core::error::Error for ApplicationError {}
+
+/// An error structure of the Greengrass IPC model, obtained through [`ApplicationError::downcast`].
+///
+/// Implemented by the error types in [`crate::protocol::generated`].
+pub trait ModeledError: DeserializeOwned {
+    /// The code of the error.
+    const CODE: ErrorCode;
+}
+
+/// The kind of an [`ApplicationError`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum ErrorCode {
+    /// An internal error in the service.
+    ServiceError,
+    /// The requested resource doesn't exist.
+    ResourceNotFoundError,
+    /// The component isn't authorized to perform the operation.
+    UnauthorizedError,
+    /// The request has invalid arguments.
+    InvalidArgumentsError,
+    /// The request conflicts with the current state of the resource.
+    ConflictError,
+    /// The expected version of the resource didn't match.
+    FailedUpdateConditionCheckError,
+    /// The requested component doesn't exist.
+    ComponentNotFoundError,
+    /// The token is invalid.
+    InvalidTokenError,
+    /// The artifacts directory path is invalid.
+    InvalidArtifactsDirectoryPathError,
+    /// The recipe directory path is invalid.
+    InvalidRecipeDirectoryPathError,
+    /// The credentials are invalid.
+    InvalidCredentialError,
+    /// The client device auth token is invalid.
+    InvalidClientDeviceAuthTokenError,
+    /// An error not known to this crate.
+    Other(String),
+}
+
+impl ErrorCode {
+    /// The error code as sent by the server.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ServiceError => "ServiceError",
+            Self::ResourceNotFoundError => "ResourceNotFoundError",
+            Self::UnauthorizedError => "UnauthorizedError",
+            Self::InvalidArgumentsError => "InvalidArgumentsError",
+            Self::ConflictError => "ConflictError",
+            Self::FailedUpdateConditionCheckError => "FailedUpdateConditionCheckError",
+            Self::ComponentNotFoundError => "ComponentNotFoundError",
+            Self::InvalidTokenError => "InvalidTokenError",
+            Self::InvalidArtifactsDirectoryPathError => "InvalidArtifactsDirectoryPathError",
+            Self::InvalidRecipeDirectoryPathError => "InvalidRecipeDirectoryPathError",
+            Self::InvalidCredentialError => "InvalidCredentialError",
+            Self::InvalidClientDeviceAuthTokenError => "InvalidClientDeviceAuthTokenError",
+            Self::Other(code) => code,
+        }
+    }
+}
+
+impl From<&str> for ErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "ServiceError" => Self::ServiceError,
+            "ResourceNotFoundError" => Self::ResourceNotFoundError,
+            "UnauthorizedError" => Self::UnauthorizedError,
+            "InvalidArgumentsError" => Self::InvalidArgumentsError,
+            "ConflictError" => Self::ConflictError,
+            "FailedUpdateConditionCheckError" => Self::FailedUpdateConditionCheckError,
+            "ComponentNotFoundError" => Self::ComponentNotFoundError,
+            "InvalidTokenError" => Self::InvalidTokenError,
+            "InvalidArtifactsDirectoryPathError" => Self::InvalidArtifactsDirectoryPathError,
+            "InvalidRecipeDirectoryPathError" => Self::InvalidRecipeDirectoryPathError,
+            "InvalidCredentialError" => Self::InvalidCredentialError,
+            "InvalidClientDeviceAuthTokenError" => Self::InvalidClientDeviceAuthTokenError,
+            code => Self::Other(code.into()),
+        }
+    }
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        match Self::from(code.as_str()) {
+            Self::Other(_) => Self::Other(code),
+            known => known,
+        }
+    }
+}
+
+impl From<ErrorCode> for String {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::Other(code) => code,
+            known => known.as_str().into(),
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -1,8 +1,43 @@
-use super::Message;
+use super::{
+    super::{Operation, StreamingOperation},
+    ErrorCode, Message,
+};
 use core::num::NonZeroU64;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// The operation to defer a component update.
+#[derive(Debug)]
+pub struct DeferComponentUpdate;
+
+impl Operation for DeferComponentUpdate {
+    const NAME: &'static str = "aws.greengrass#DeferComponentUpdate";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#DeferComponentUpdateRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = DeferComponentUpdateRequest<'r>;
+    type Response = DeferComponentUpdateResponse;
+}
+
+/// The operation to subscribe to component updates.
+#[derive(Debug)]
+pub struct SubscribeToComponentUpdates;
+
+impl Operation for SubscribeToComponentUpdates {
+    const NAME: &'static str = "aws.greengrass#SubscribeToComponentUpdates";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#SubscribeToComponentUpdatesRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = ComponentUpdateSubscriptionRequest;
+    type Response = ComponentUpdateSubscriptionResponse;
+}
+
+impl StreamingOperation for SubscribeToComponentUpdates {
+    type Event = ComponentUpdateSubscriptionResponse;
+}
+
 /// A request to defer a component update.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct DeferComponentUpdateRequest<'a> {
@@ -22,18 +57,19 @@ impl<'m> DeferComponentUpdateRequest<'m> {
         component_name: Option<&'m str>,
         recheck_after_ms: RecheckAfterMs,
     ) -> Message<'m, Self> {
-        let payload = DeferComponentUpdateRequest {
-            deployment_id,
-            message: component_name,
-            recheck_after_ms,
-        };
+        let payload = Self::new_payload(deployment_id, component_name, recheck_after_ms);
 
-        Message::ipc_call(
-            "aws.greengrass#DeferComponentUpdateRequest",
-            "aws.greengrass#DeferComponentUpdate",
-            stream_id,
-            Some(payload),
-        )
+        Message::operation::<DeferComponentUpdate>(stream_id, payload)
+    }
+
+    /// Creates the payload of a new `DeferComponentUpdateRequest`, for
+    /// [`crate::Connection::invoke`].
+    pub fn new_payload(
+        deployment_id: Uuid,
+        message: Option<&'m str>,
+        recheck_after_ms: RecheckAfterMs,
+    ) -> Self {
+        DeferComponentUpdateRequest { deployment_id, message, recheck_after_ms }
     }
 
     /// Returns the deployment ID parameter.
@@ -99,8 +135,8 @@ impl ComponentUpdateSubscriptionRequest {
     /// Creates a new `ComponentUpdateSubscriptionRequest`.
     pub fn new(stream_id: i32) -> Message<'static, Self> {
         Message::ipc_call(
-            "aws.greengrass#SubscribeToComponentUpdatesRequest",
-            "aws.greengrass#SubscribeToComponentUpdates",
+            SubscribeToComponentUpdates::REQUEST_MODEL_TYPE,
+            SubscribeToComponentUpdates::NAME,
             stream_id,
             None,
         )
@@ -137,7 +173,7 @@ impl ComponentUpdateSubscriptionResponse {
 }
 
 /// An event that occurs before a component update.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PreComponentUpdateEvent {
     #[serde(rename = "deploymentId")]
     deployment_id: Uuid,
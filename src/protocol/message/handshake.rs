@@ -1,5 +1,5 @@
 use super::{
-    super::headers::{self, Headers, MessageFlags, MessageType},
+    super::headers::{Headers, MessageFlags, MessageType},
     Message,
 };
 use crate::Result;
@@ -9,8 +9,8 @@ impl<'c> ConnectRequest<'c> {
     /// Creates a new `ConnectRequest`.
     pub fn new(auth_token: &'c str) -> Result<Message<'static, Self>> {
         let mut headers = Headers::new(FIRST_STREAM_ID, MessageType::Connect, MessageFlags::none());
-        headers.insert(":version", headers::Value::String("0.1.0".into()));
-        headers.insert(":content-type", headers::Value::String("application/json".into()));
+        headers.set_version("0.1.0");
+        headers.set_content_type("application/json");
 
         Ok(Message::new(headers, Some(ConnectRequest { auth_token })))
     }
@@ -0,0 +1,48 @@
+//! The JSON backend of payloads.
+//!
+//! Payloads are always encoded with `serde_json`, so the bytes on the wire don't depend on the
+//! enabled features. With the `simd-json` feature, payloads decoded into owned types are parsed in
+//! place with `simd-json`. Types borrowing from the payload are always decoded with `serde_json`.
+//!
+//! Decoding errors keep their origin: [`crate::Error::Json`] from `serde_json` and
+//! `Error::SimdJson` from `simd-json`.
+
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// Encode `value` as JSON into `writer`.
+pub(crate) fn to_writer<T>(writer: &mut impl Write, value: &T) -> Result<()>
+where
+    T: Serialize + ?Sized,
+{
+    serde_json::to_writer(writer, value).map_err(Into::into)
+}
+
+/// Decode a JSON payload.
+pub(crate) fn from_slice<'de, T>(bytes: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    serde_json::from_slice(bytes).map_err(Into::into)
+}
+
+/// Decode a JSON payload, possibly modifying the bytes.
+#[cfg(not(feature = "simd-json"))]
+pub(crate) fn from_mut_slice<'de, T>(bytes: &'de mut [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    from_slice(bytes)
+}
+
+/// Decode a JSON payload, parsing it in place.
+#[cfg(feature = "simd-json")]
+pub(crate) fn from_mut_slice<'de, T>(bytes: &'de mut [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    simd_json::serde::from_slice(bytes).map_err(Into::into)
+}
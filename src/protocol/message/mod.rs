@@ -1,25 +1,35 @@
-use core::fmt::Debug;
-use crc::{Crc, CRC_32_ISO_HDLC};
-use endi::{ReadBytes, WriteBytes};
-use serde::{Deserialize, Serialize};
-use std::io::Write;
+use bytes::{BufMut, BytesMut};
+use core::{cell::RefCell, fmt::Debug, ops::Range};
+use endi::ReadBytes;
+use std::borrow::Cow;
 use tracing::trace;
 
-use serde_json::{from_slice, to_vec};
+use serde_json::from_slice;
 
+mod application_error;
 mod component_update;
 mod handshake;
+mod json;
+mod owned;
+mod payload;
 mod state;
 
+pub use application_error::*;
 pub use component_update::*;
 pub use handshake::*;
+pub use owned::OwnedMessage;
+pub use payload::*;
 pub use state::*;
 
 use crate::{Error, Result};
 
 use super::{
-    headers::{self, Headers, MessageFlags, MessageType},
-    prelude::Prelude,
+    headers::{
+        header_size_in_bytes, write_header_as_bytes, Headers, MessageFlags, MessageType, Value,
+        CONTENT_TYPE,
+    },
+    prelude::{Prelude, SIZE as PRELUDE_SIZE},
+    Operation, CRC32,
 };
 
 /// A message.
@@ -43,12 +53,20 @@ impl<'m, Payload> Message<'m, Payload> {
         payload: Option<Payload>,
     ) -> Self {
         let mut headers = Headers::new(stream_id, MessageType::Application, MessageFlags::none());
-        headers.insert("service-model-type", headers::Value::String(service_model_type.into()));
-        headers.insert("operation", headers::Value::String(operation.into()));
+        headers.set_service_model_type(service_model_type);
+        headers.set_operation(operation);
 
         Self::new(headers, payload)
     }
 
+    /// Creates a new message calling the operation `Op`.
+    pub fn operation<Op>(stream_id: i32, payload: Payload) -> Self
+    where
+        Op: Operation<Request<'m> = Payload>,
+    {
+        Self::ipc_call(Op::REQUEST_MODEL_TYPE, Op::NAME, stream_id, Some(payload))
+    }
+
     /// The headers.
     pub fn headers(&self) -> &Headers<'m> {
         &self.headers
@@ -58,96 +76,172 @@ impl<'m, Payload> Message<'m, Payload> {
     pub fn payload(&self) -> Option<&Payload> {
         self.payload.as_ref()
     }
+
+    /// Converts the message into its payload.
+    pub fn into_payload(self) -> Option<Payload> {
+        self.payload
+    }
+
+    /// Converts the message into an owned message, keeping the payload as is.
+    pub fn into_owned(self) -> Message<'static, Payload> {
+        Message { headers: self.headers.to_owned(), payload: self.payload }
+    }
 }
 
 impl<Payload> Message<'_, Payload>
 where
-    Payload: Serialize + Debug,
+    Payload: EncodePayload + Debug,
 {
     /// Converts the message to bytes.
+    ///
+    /// See [`Message::encode_into`] for the details, and to reuse a buffer instead.
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
-        let mut bytes = Vec::with_capacity(1024);
-
-        let headers_len = self.headers.size_in_bytes()?;
-        let payload = self.payload.as_ref().map(|p| to_vec(p)).transpose()?.unwrap_or_default();
-        let payload_len: u32 = payload.len().try_into().map_err(|_| Error::BufferTooLarge {
-            size: payload.len(),
-            max_size: u32::MAX as usize,
-        })?;
-        let total_len =
-            // 8 bytes prelude + 4 bytes CRC checksum of prelude.
-            12 +
-            headers_len +
-            payload_len +
-            // 4 bytes CRC checksum of the whole message.
-            4;
-
-        let prelude = Prelude::new(total_len as usize, headers_len as usize)?;
-        prelude.write_as_bytes(&mut bytes)?;
-
-        // Then the headers and payload.
-        self.headers.write_as_bytes(&mut bytes)?;
-        bytes.write_all(&payload)?;
+        let mut bytes = Vec::new();
+        self.encode_appending(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// The size of the encoded message in bytes.
+    ///
+    /// Unless the payload knows its [`EncodePayload::encoded_len`], this encodes the payload, so
+    /// there's no need to call it before encoding the message.
+    pub fn encoded_len(&self) -> Result<usize> {
+        let mut headers_len = self.headers.size_in_bytes()? as usize;
+        if let Some(content_type) = self.content_type_header()? {
+            headers_len += header_size_in_bytes(CONTENT_TYPE, &content_type)? as usize;
+        }
+        let payload_len = self.payload.as_ref().map_or(Ok(0), EncodePayload::encoded_len)?;
+        let total_len = PRELUDE_SIZE + headers_len + payload_len + 4;
+
+        Ok(total_len)
+    }
+
+    /// Encodes the message into `buf`, returning the number of bytes written.
+    ///
+    /// The payload is encoded according to its [`EncodePayload::content_type`], which is added as
+    /// the `:content-type` header if it isn't JSON. Fails if the header is already set to a
+    /// different content type, or if `buf` runs out of space.
+    ///
+    /// The payload is only encoded once: the message is assembled in a scratch buffer reused by
+    /// the thread and then copied into `buf`.
+    pub fn encode_into(&self, buf: &mut impl BufMut) -> Result<usize> {
+        with_scratch(|scratch| {
+            let len = self.encode_appending(scratch)?;
+            if buf.remaining_mut() < len {
+                return Err(std::io::Error::from(std::io::ErrorKind::WriteZero).into());
+            }
+            buf.put_slice(scratch);
+
+            Ok(len)
+        })
+    }
+
+    /// Encodes the message at the end of `buf`, returning the number of bytes written.
+    ///
+    /// The payload is encoded straight into `buf`, and the prelude and checksum are filled in once
+    /// its size is known. On error, `buf` is left as it was.
+    pub(crate) fn encode_appending(&self, buf: &mut impl EncodeBuf) -> Result<usize> {
+        let start = buf.len();
+        let res = self.encode_appending_at(buf, start);
+        if res.is_err() {
+            buf.truncate(start);
+        }
+
+        res
+    }
+
+    fn encode_appending_at(&self, buf: &mut impl EncodeBuf, start: usize) -> Result<usize> {
+        let content_type = self.content_type_header()?;
+        // Filled in once the lengths are known.
+        buf.put_bytes(0, PRELUDE_SIZE);
+        self.headers.write_as_bytes(&mut (&mut *buf).writer())?;
+        if let Some(content_type) = &content_type {
+            write_header_as_bytes(CONTENT_TYPE, content_type, &mut (&mut *buf).writer())?;
+        }
+        let headers_len = buf.len() - start - PRELUDE_SIZE;
+        if let Some(payload) = &self.payload {
+            payload.encode(&mut (&mut *buf).writer())?;
+        }
+
+        // 4 bytes CRC checksum of the whole message.
+        let total_len = buf.len() - start + 4;
+        let prelude = Prelude::new(total_len, headers_len)?;
+        prelude.write_as_bytes(&mut &mut buf.as_mut()[start..start + PRELUDE_SIZE])?;
 
         // Finally the CRC checksum of the whole message.
-        let crc32 = Crc::<u32>::new(&CRC_32_ISO_HDLC);
-        let checksum = crc32.checksum(&bytes);
-        bytes.write_u32(endi::Endian::Big, checksum)?;
+        let checksum = CRC32.checksum(&buf.as_mut()[start..]);
+        buf.put_u32(checksum);
 
-        Ok(bytes)
+        Ok(total_len)
+    }
+
+    /// The `:content-type` header to send after the headers, if the payload needs one added.
+    fn content_type_header(&self) -> Result<Option<Value<'_>>> {
+        let Some(p) = &self.payload else {
+            return Ok(None);
+        };
+        match self.headers.content_type() {
+            Some(content_type) if !media_type_eq(content_type, p.content_type()) => {
+                Err(Error::InvalidHeader(format!(
+                    "content type `{content_type}` doesn't match the payload's `{}`",
+                    p.content_type()
+                )))
+            }
+            Some(_) => Ok(None),
+            None if media_type_eq(p.content_type(), JSON_CONTENT_TYPE) => Ok(None),
+            None => Ok(Some(Value::String(Cow::Borrowed(p.content_type())))),
+        }
     }
 }
 
 impl<'m, Payload> Message<'m, Payload>
 where
-    Payload: Deserialize<'m> + Debug,
+    Payload: DecodePayload<'m> + Debug,
 {
     /// Parses a message from bytes.
+    ///
+    /// The payload is decoded according to the `:content-type` header, JSON if there is none.
+    /// Fails if the payload type doesn't accept the content type. The bytes are advanced past the
+    /// message. Truncated or otherwise malformed input results in an error, never a panic.
+    ///
+    /// JSON payloads are always decoded with `serde_json`, even with the `simd-json` feature, since
+    /// the payload can only be borrowed here. Use [`OwnedMessage::message_owned`] for the
+    /// `simd-json` backend.
     pub fn from_bytes(bytes: &mut &'m [u8]) -> Result<Self> {
         trace!("Parsing message from bytes: {:02X?}", bytes);
-        let crc32 = Crc::<u32>::new(&CRC_32_ISO_HDLC);
-        let msg_checksum = crc32.checksum(&bytes[..bytes.len() - 4]);
-        let prelude =
-            Prelude::from_bytes(bytes).map_err(|_| Error::Protocol("Invalid prelude".into()))?;
-
-        trace!("Prelede: {:?}", prelude);
-        let headers = Headers::from_bytes(&mut &bytes[..prelude.headers_len()])?;
-        // The `unwrap` call here can only panic if the headers length is > `u32::MAX` and
-        // `from_bytes` ensures that it is not.
-        if headers.size_in_bytes().unwrap() as usize != prelude.headers_len() {
-            return Err(Error::Protocol("Incorrect header length".into()));
-        }
+        let (headers, payload) = split_message(bytes)?;
+        let headers = parse_headers(headers, None)?;
 
-        trace!("Headers: {:?}", headers);
-        *bytes = &bytes[prelude.headers_len()..];
+        Self::from_parts(headers, payload)
+    }
 
-        // 8 bytes prelude + 4 bytes CRC checksum of prelude + header bytes already parsed.
-        let msg_crc_offset = prelude.total_len() - 12 - prelude.headers_len() - 4;
-        if matches!(headers.message_type(), MessageType::ApplicationError) {
-            let err_msg = if msg_crc_offset != 0 {
-                from_slice(&bytes[..msg_crc_offset])
-                    .map_err(|e| Error::Protocol(format!("Invalid payload: {e}")))?
-            } else {
-                String::new()
-            };
+    /// Builds a message from its parsed headers and raw payload.
+    ///
+    /// Error messages are turned into the corresponding [`Error`].
+    fn from_parts(headers: Headers<'m>, payload: &'m [u8]) -> Result<Self> {
+        check_received(&headers, payload, Payload::accepts)?;
+        let payload = if !payload.is_empty() { Some(Payload::decode(payload)?) } else { None };
+        trace!("Payload: {:?}", payload);
+        trace!("Succesfully parsed message");
 
-            return Err(Error::Application(err_msg));
-        }
-        let payload = if msg_crc_offset != 0 {
-            from_slice(&bytes[..msg_crc_offset])
-                .map_err(|e| Error::Protocol(format!("Invalid payload: {e}")))?
-        } else {
-            None
-        };
+        Ok(Self::new(headers, payload))
+    }
+}
+
+impl<'m, Payload> Message<'m, Payload>
+where
+    Payload: Debug,
+{
+    /// Like [`Message::from_parts`], but decoding the payload with [`DecodePayload::decode_mut`].
+    #[cfg(feature = "simd-json")]
+    fn from_parts_mut<'p>(headers: Headers<'m>, payload: &'p mut [u8]) -> Result<Self>
+    where
+        Payload: DecodePayload<'p>,
+    {
+        check_received(&headers, payload, Payload::accepts)?;
+        let payload = if !payload.is_empty() { Some(Payload::decode_mut(payload)?) } else { None };
         trace!("Payload: {:?}", payload);
-        *bytes = &bytes[msg_crc_offset..];
-        if msg_checksum
-            != bytes
-                .read_u32(endi::Endian::Big)
-                .map_err(|_| Error::Protocol("Invalid encoding".into()))?
-        {
-            return Err(Error::ChecksumMismatch);
-        }
         trace!("Succesfully parsed message");
 
         Ok(Self::new(headers, payload))
@@ -166,3 +260,161 @@ where
         }
     }
 }
+
+/// A growable buffer that messages can be encoded into in a single pass.
+pub(crate) trait EncodeBuf: BufMut + AsMut<[u8]> {
+    /// The number of bytes in the buffer.
+    fn len(&self) -> usize;
+
+    /// Shorten the buffer to `len` bytes.
+    fn truncate(&mut self, len: usize);
+}
+
+impl EncodeBuf for Vec<u8> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+}
+
+impl EncodeBuf for BytesMut {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+}
+
+/// Run `f` with an empty buffer reused by the thread.
+///
+/// If the buffer is already in use, from within an outer call, `f` gets a new one instead.
+fn with_scratch<T>(f: impl FnOnce(&mut Vec<u8>) -> T) -> T {
+    thread_local! {
+        static SCRATCH: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    SCRATCH.with(|scratch| {
+        let mut fallback = Vec::new();
+        let mut scratch = scratch.try_borrow_mut();
+        let scratch = scratch.as_deref_mut().unwrap_or(&mut fallback);
+        scratch.clear();
+        let res = f(scratch);
+        // Don't hold on to the memory of an unusually large message.
+        scratch.clear();
+        scratch.shrink_to(MAX_SCRATCH_CAPACITY);
+
+        res
+    })
+}
+
+const MAX_SCRATCH_CAPACITY: usize = 64 * 1024;
+
+/// Turn error messages into the corresponding [`Error`], and check that the content type of the
+/// payload, if any, is accepted.
+fn check_received(headers: &Headers<'_>, payload: &[u8], accepts: fn(&str) -> bool) -> Result<()> {
+    match headers.message_type() {
+        MessageType::ApplicationError => {
+            let error = ApplicationError::from_message(headers, payload);
+
+            return Err(Error::Application(error));
+        }
+        MessageType::ProtocolError => {
+            let error = server_diagnostics(headers, payload);
+
+            return Err(Error::Protocol(error));
+        }
+        MessageType::InternalError => {
+            let error = server_diagnostics(headers, payload);
+
+            return Err(Error::InternalServer(error));
+        }
+        _ => (),
+    }
+    if !payload.is_empty() {
+        let content_type = headers.content_type().unwrap_or(JSON_CONTENT_TYPE);
+        if !accepts(content_type) {
+            return Err(Error::Protocol(format!("Unexpected content type `{content_type}`")));
+        }
+    }
+
+    Ok(())
+}
+
+/// Split a message into its headers and payload bytes, after verifying its checksums.
+///
+/// The bytes are advanced past the message.
+fn split_message<'m>(bytes: &mut &'m [u8]) -> Result<(&'m [u8], &'m [u8])> {
+    let input: &'m [u8] = bytes;
+    let prelude = Prelude::from_bytes(bytes)?;
+    trace!("Prelude: {:?}", prelude);
+
+    let message = input
+        .get(..prelude.total_len())
+        .ok_or(Error::Protocol("Invalid encoding: truncated message".into()))?;
+    // The prelude ensures the message is large enough to hold itself, the headers and the
+    // checksum.
+    let (content, mut checksum) = message.split_at(prelude.total_len() - 4);
+    if CRC32.checksum(content)
+        != checksum
+            .read_u32(endi::Endian::Big)
+            .map_err(|_| Error::Protocol("Invalid encoding".into()))?
+    {
+        return Err(Error::ChecksumMismatch);
+    }
+    *bytes = &input[prelude.total_len()..];
+
+    Ok(content[PRELUDE_SIZE..].split_at(prelude.headers_len()))
+}
+
+/// Parse the headers of a message, which must take up all of the given bytes.
+///
+/// If `ranges` is given, the range of the bytes taken by each header is recorded in it.
+fn parse_headers<'h>(
+    bytes: &'h [u8],
+    ranges: Option<&mut Vec<Range<usize>>>,
+) -> Result<Headers<'h>> {
+    let headers = match ranges {
+        Some(ranges) => Headers::from_bytes_with_ranges(&mut &bytes[..], ranges)?,
+        None => Headers::from_bytes(&mut &bytes[..])?,
+    };
+    if headers.size_in_bytes()? as usize != bytes.len() {
+        return Err(Error::Protocol("Incorrect header length".into()));
+    }
+    trace!("Headers: {:?}", headers);
+
+    Ok(headers)
+}
+
+/// Describe the error reported by a `ProtocolError` or `InternalError` message.
+///
+/// The payload is usually a JSON object with a `message`, but anything else is kept as is. Any
+/// non-standard headers are appended, since they may help figuring out what went wrong.
+fn server_diagnostics(headers: &Headers<'_>, payload: &[u8]) -> String {
+    let mut diagnostics = match from_slice::<serde_json::Value>(payload) {
+        Ok(serde_json::Value::Object(object)) => match object.get("message") {
+            Some(serde_json::Value::String(message)) => message.clone(),
+            _ => serde_json::Value::Object(object).to_string(),
+        },
+        Ok(serde_json::Value::String(message)) => message,
+        _ => String::from_utf8_lossy(payload).trim().to_owned(),
+    };
+    if diagnostics.is_empty() {
+        diagnostics.push_str("No details provided by the server");
+    }
+
+    let extra_headers: Vec<_> = headers
+        .iter()
+        .filter(|(name, _)| !name.starts_with(':'))
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect();
+    if !extra_headers.is_empty() {
+        diagnostics.push_str(&format!(" ({})", extra_headers.join(", ")));
+    }
+
+    diagnostics
+}
@@ -0,0 +1,118 @@
+use core::{fmt::Debug, ops::Range};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use enumflags2::BitFlags;
+
+#[cfg(feature = "simd-json")]
+use super::with_scratch;
+use super::{parse_headers, split_message, DecodePayload, Message};
+use crate::{
+    protocol::{Headers, MessageFlags, MessageType},
+    Result,
+};
+
+/// A message that owns its bytes.
+///
+/// Unlike [`Message`], which borrows the bytes it was parsed from, an `OwnedMessage` keeps
+/// reference-counted [`Bytes`] views of the headers and payload into the received message. It can
+/// be cloned cheaply and handed to other tasks without copying. The payload is only deserialized
+/// on demand, through [`OwnedMessage::message`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedMessage {
+    headers: Bytes,
+    // Where each header is in `headers`, so they don't need to be parsed from scratch again.
+    header_ranges: Arc<[Range<usize>]>,
+    payload: Bytes,
+    stream_id: i32,
+    message_type: MessageType,
+    message_flags: BitFlags<MessageFlags>,
+}
+
+impl OwnedMessage {
+    /// Parses a message from bytes, verifying its checksums and headers.
+    ///
+    /// Any bytes after the message are ignored.
+    pub fn from_bytes(bytes: Bytes) -> Result<Self> {
+        let mut remaining = &bytes[..];
+        let (headers, payload) = split_message(&mut remaining)?;
+        let mut header_ranges = Vec::new();
+        let parsed = parse_headers(headers, Some(&mut header_ranges))?;
+        let (stream_id, message_type, message_flags) =
+            (parsed.stream_id(), parsed.message_type(), parsed.message_flags());
+
+        Ok(Self {
+            headers: bytes.slice_ref(headers),
+            header_ranges: header_ranges.into(),
+            payload: bytes.slice_ref(payload),
+            stream_id,
+            message_type,
+            message_flags,
+        })
+    }
+
+    /// The headers.
+    ///
+    /// They are read from the positions found by [`OwnedMessage::from_bytes`], in linear time.
+    pub fn headers(&self) -> Headers<'_> {
+        // Already parsed successfully by `from_bytes`, and reading the same bytes again can't fail.
+        Headers::from_ranges(
+            &self.headers,
+            &self.header_ranges,
+            self.stream_id,
+            self.message_type,
+            self.message_flags,
+        )
+        .expect("valid headers")
+    }
+
+    /// The raw payload.
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// The stream ID of the message.
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    /// The type of the message.
+    pub fn message_type(&self) -> MessageType {
+        self.message_type
+    }
+
+    /// The flags set on the message.
+    pub fn message_flags(&self) -> BitFlags<MessageFlags> {
+        self.message_flags
+    }
+
+    /// Deserializes the payload, borrowing from this message.
+    ///
+    /// Error messages are turned into the corresponding [`crate::Error`], as with
+    /// [`Message::from_bytes`].
+    pub fn message<'m, Payload>(&'m self) -> Result<Message<'m, Payload>>
+    where
+        Payload: DecodePayload<'m> + Debug,
+    {
+        Message::from_parts(self.headers(), &self.payload)
+    }
+
+    /// Deserializes the payload into an owned type.
+    ///
+    /// With the `simd-json` feature, JSON payloads are parsed in place with `simd-json`, from a
+    /// copy in a buffer reused by the thread. Otherwise, this is the same as
+    /// [`OwnedMessage::message`].
+    pub fn message_owned<Payload>(&self) -> Result<Message<'static, Payload>>
+    where
+        Payload: for<'de> DecodePayload<'de> + Debug,
+    {
+        #[cfg(feature = "simd-json")]
+        return with_scratch(|scratch| {
+            scratch.extend_from_slice(&self.payload);
+
+            Message::from_parts_mut(self.headers(), scratch).map(Message::into_owned)
+        });
+        #[cfg(not(feature = "simd-json"))]
+        self.message().map(Message::into_owned)
+    }
+}
@@ -0,0 +1,229 @@
+use std::{borrow::Cow, io::Write};
+
+use serde::{Deserialize, Serialize};
+
+use super::json;
+use crate::{Error, Result};
+
+/// The content type of JSON payloads, assumed if a message has no `:content-type` header.
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// The content type of raw binary payloads.
+pub const BINARY_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// The content type of plain text payloads.
+pub const TEXT_CONTENT_TYPE: &str = "text/plain";
+
+/// A payload that can be encoded into a message.
+///
+/// Implemented for every [`Serialize`] type as JSON, and for [`Binary`] and [`Text`]. Implement it
+/// (along with [`DecodePayload`]) for other formats. The content type is written to the
+/// `:content-type` header of the message, unless it's JSON, which is what the server assumes
+/// without the header.
+pub trait EncodePayload {
+    /// The content type of the encoded payload.
+    fn content_type(&self) -> &str;
+
+    /// Write the encoded payload into `writer`.
+    fn encode(&self, writer: &mut impl Write) -> Result<()>;
+
+    /// The size of the encoded payload in bytes.
+    ///
+    /// Only used by [`super::Message::encoded_len`], since messages are encoded in a single pass.
+    /// By default, the payload is encoded into a writer that only counts the bytes, so implement
+    /// it if the size is known.
+    fn encoded_len(&self) -> Result<usize> {
+        let mut counter = ByteCounter(0);
+        self.encode(&mut counter)?;
+
+        Ok(counter.0)
+    }
+}
+
+/// A payload that can be decoded from a message.
+///
+/// Implemented for every [`Deserialize`] type as JSON, and for [`Binary`] and [`Text`].
+pub trait DecodePayload<'de>: Sized {
+    /// Whether payloads of the given content type can be decoded.
+    ///
+    /// Messages of any other content type are rejected.
+    fn accepts(content_type: &str) -> bool;
+
+    /// Decode the payload.
+    fn decode(bytes: &'de [u8]) -> Result<Self>;
+
+    /// Decode the payload from bytes it's free to modify, e.g. to parse them in place.
+    ///
+    /// Used when decoding into an owned type, from a copy of the payload. The same as
+    /// [`DecodePayload::decode`] by default.
+    fn decode_mut(bytes: &'de mut [u8]) -> Result<Self> {
+        Self::decode(bytes)
+    }
+}
+
+impl<T> EncodePayload for T
+where
+    T: Serialize + ?Sized,
+{
+    fn content_type(&self) -> &str {
+        JSON_CONTENT_TYPE
+    }
+
+    fn encode(&self, writer: &mut impl Write) -> Result<()> {
+        json::to_writer(writer, self)
+    }
+}
+
+impl<'de, T> DecodePayload<'de> for T
+where
+    T: Deserialize<'de>,
+{
+    fn accepts(content_type: &str) -> bool {
+        media_type_eq(content_type, JSON_CONTENT_TYPE)
+    }
+
+    fn decode(bytes: &'de [u8]) -> Result<Self> {
+        json::from_slice(bytes)
+    }
+
+    fn decode_mut(bytes: &'de mut [u8]) -> Result<Self> {
+        json::from_mut_slice(bytes)
+    }
+}
+
+/// A raw binary payload, e.g. an `application/octet-stream` body.
+///
+/// Payloads of any content type can be decoded as `Binary`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binary<'b> {
+    bytes: Cow<'b, [u8]>,
+    content_type: Cow<'b, str>,
+}
+
+impl<'b> Binary<'b> {
+    /// Create a new `application/octet-stream` payload.
+    pub fn new<B>(bytes: B) -> Self
+    where
+        B: Into<Cow<'b, [u8]>>,
+    {
+        Self { bytes: bytes.into(), content_type: BINARY_CONTENT_TYPE.into() }
+    }
+
+    /// Set a more specific content type than `application/octet-stream`.
+    pub fn with_content_type<C>(mut self, content_type: C) -> Self
+    where
+        C: Into<Cow<'b, str>>,
+    {
+        self.content_type = content_type.into();
+
+        self
+    }
+
+    /// The bytes.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Converts the payload into its bytes.
+    pub fn into_bytes(self) -> Cow<'b, [u8]> {
+        self.bytes
+    }
+}
+
+impl EncodePayload for Binary<'_> {
+    fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    fn encode(&self, writer: &mut impl Write) -> Result<()> {
+        writer.write_all(&self.bytes).map_err(Into::into)
+    }
+
+    fn encoded_len(&self) -> Result<usize> {
+        Ok(self.bytes.len())
+    }
+}
+
+impl<'de> DecodePayload<'de> for Binary<'de> {
+    fn accepts(_content_type: &str) -> bool {
+        true
+    }
+
+    fn decode(bytes: &'de [u8]) -> Result<Self> {
+        Ok(Self::new(bytes))
+    }
+}
+
+/// A UTF-8 `text/plain` payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Text<'t>(Cow<'t, str>);
+
+impl<'t> Text<'t> {
+    /// Create a new text payload.
+    pub fn new<T>(text: T) -> Self
+    where
+        T: Into<Cow<'t, str>>,
+    {
+        Self(text.into())
+    }
+
+    /// The text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Converts the payload into its text.
+    pub fn into_inner(self) -> Cow<'t, str> {
+        self.0
+    }
+}
+
+impl EncodePayload for Text<'_> {
+    fn content_type(&self) -> &str {
+        TEXT_CONTENT_TYPE
+    }
+
+    fn encode(&self, writer: &mut impl Write) -> Result<()> {
+        writer.write_all(self.0.as_bytes()).map_err(Into::into)
+    }
+
+    fn encoded_len(&self) -> Result<usize> {
+        Ok(self.0.len())
+    }
+}
+
+impl<'de> DecodePayload<'de> for Text<'de> {
+    fn accepts(content_type: &str) -> bool {
+        media_type_eq(content_type, TEXT_CONTENT_TYPE)
+    }
+
+    fn decode(bytes: &'de [u8]) -> Result<Self> {
+        core::str::from_utf8(bytes)
+            .map(Self::new)
+            .map_err(|_| Error::Protocol("Invalid payload: invalid UTF-8".into()))
+    }
+}
+
+/// Whether two content types have the same media type, ignoring any parameters like `charset`.
+pub(crate) fn media_type_eq(content_type: &str, other: &str) -> bool {
+    media_type(content_type).eq_ignore_ascii_case(media_type(other))
+}
+
+fn media_type(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or(content_type).trim()
+}
+
+/// A writer that only counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
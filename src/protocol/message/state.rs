@@ -1,6 +1,20 @@
-use super::Message;
+use super::{super::Operation, ErrorCode, Message};
 use serde::{Deserialize, Serialize};
 
+/// The operation to update the state of a component.
+#[derive(Debug)]
+pub struct UpdateState;
+
+impl Operation for UpdateState {
+    const NAME: &'static str = "aws.greengrass#UpdateState";
+    const REQUEST_MODEL_TYPE: &'static str = "aws.greengrass#UpdateStateRequest";
+    const ERRORS: &'static [ErrorCode] =
+        &[ErrorCode::ServiceError, ErrorCode::ResourceNotFoundError];
+
+    type Request<'r> = UpdateStateRequest;
+    type Response = UpdateStateResponse;
+}
+
 /// A request to update the state of a component.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct UpdateStateRequest {
@@ -11,14 +25,12 @@ pub struct UpdateStateRequest {
 impl UpdateStateRequest {
     /// Creates a new `UpdateStateRequest`.
     pub fn new(stream_id: i32, state: crate::LifecycleState) -> Message<'static, Self> {
-        let payload = UpdateStateRequest { state };
+        Message::operation::<UpdateState>(stream_id, Self::new_payload(state))
+    }
 
-        Message::ipc_call(
-            "aws.greengrass#UpdateStateRequest",
-            "aws.greengrass#UpdateState",
-            stream_id,
-            Some(payload),
-        )
+    /// Creates the payload of a new `UpdateStateRequest`, for [`crate::Connection::invoke`].
+    pub fn new_payload(state: crate::LifecycleState) -> Self {
+        UpdateStateRequest { state }
     }
 }
 
@@ -1,22 +1,49 @@
 //! The protocol module contains the definitions of the protocol messages and the (de)serialization
 //! logic for them.
 
+mod codec;
+pub(crate) use codec::Decoded;
+pub use codec::MessageCodec;
+pub mod generated;
 pub mod headers;
 mod message;
+mod operation;
 pub mod prelude;
 
 pub use headers::{Headers, MessageFlags, MessageType};
 pub use message::*;
+pub use operation::{Operation, StreamingOperation};
+
+use crc::{Crc, CRC_32_ISO_HDLC};
+
+/// The CRC32 used for the checksums of messages, with its lookup table computed at compile time.
+pub(crate) static CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
 
 #[cfg(test)]
 mod tests {
+    use core::{cell::Cell, time::Duration};
+    use std::{
+        borrow::Cow,
+        io::Write,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use arbitrary::Unstructured;
+    use bytes::{Bytes, BytesMut};
+    use crc::{Crc, CRC_32_ISO_HDLC};
+    use enumflags2::BitFlag;
     use pretty_hex::PrettyHex;
     use serde_json::json;
 
     use super::{
-        headers::{Headers, MessageFlags, MessageType},
-        ComponentUpdateSubscriptionResponse, ConnectResponse, Message,
+        generated,
+        headers::{self, Headers, MessageFlags, MessageType, Value},
+        prelude::{self, Prelude, MAX_HEADERS_SIZE, MAX_MESSAGE_SIZE},
+        Binary, ComponentUpdateSubscriptionResponse, ConnectResponse, DecodePayload, Decoded,
+        EncodePayload, ErrorCode, Message, MessageCodec, OwnedMessage, Text, BINARY_CONTENT_TYPE,
+        JSON_CONTENT_TYPE,
     };
+    use crate::Error;
 
     #[test]
     fn message_to_n_from_bytes() {
@@ -48,6 +75,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn parse_component_updates_response() {
         // A pre-component update event.
         let bytes = [
@@ -79,7 +107,7 @@ mod tests {
         let pre_update_event = payload.pre_update_event().unwrap();
         assert_eq!(
             pre_update_event.deployment_id(),
-            "77d00c6b-f0c6-4e14-86cb-d476f0016044".try_into().unwrap()
+            uuid::Uuid::try_from("77d00c6b-f0c6-4e14-86cb-d476f0016044").unwrap()
         );
         assert_eq!(pre_update_event.is_ggc_restarting(), false);
         assert!(payload.post_update_event().is_none());
@@ -113,8 +141,749 @@ mod tests {
         let post_update_event = payload.post_update_event().unwrap();
         assert_eq!(
             post_update_event.deployment_id(),
-            "8f55775e-8db1-4b82-a548-1916f7b361fb".try_into().unwrap()
+            uuid::Uuid::try_from("8f55775e-8db1-4b82-a548-1916f7b361fb").unwrap()
         );
         assert!(payload.pre_update_event().is_none());
     }
+
+    #[test]
+    fn parse_application_error() {
+        let headers = Headers::new(1, MessageType::ApplicationError, MessageFlags::none());
+        let payload = json!({
+            "_message": "Resource not found",
+            "_errorCode": "ResourceNotFoundError",
+            "_service": "aws.greengrass#GreengrassCoreIPC",
+            "resourceType": "Component",
+        });
+        let bytes = Message::new(headers, Some(payload)).to_bytes().unwrap();
+        let Err(Error::Application(error)) = Message::<()>::from_bytes(&mut &bytes[..]) else {
+            panic!("Expected an application error");
+        };
+        assert_eq!(error.code(), &ErrorCode::ResourceNotFoundError);
+        assert_eq!(error.message(), Some("Resource not found"));
+        assert_eq!(error.service(), Some("aws.greengrass#GreengrassCoreIPC"));
+        assert_eq!(error.details()["resourceType"], "Component");
+        let modeled = error.downcast::<generated::ResourceNotFoundError>().unwrap();
+        assert_eq!(modeled.message.as_deref(), Some("Resource not found"));
+        assert_eq!(modeled.resource_type.as_deref(), Some("Component"));
+        assert_eq!(modeled.resource_name, None);
+        assert_eq!(error.downcast::<generated::UnauthorizedError>(), None);
+
+        // Without an error code in the payload, it's taken from the model type.
+        let mut headers = Headers::new(1, MessageType::ApplicationError, MessageFlags::none());
+        headers.set_service_model_type("aws.greengrass#UnauthorizedError");
+        let bytes = Message::new(headers, Some(json!({"message": "Denied"}))).to_bytes().unwrap();
+        let Err(Error::Application(error)) = Message::<()>::from_bytes(&mut &bytes[..]) else {
+            panic!("Expected an application error");
+        };
+        assert_eq!(error.code(), &ErrorCode::UnauthorizedError);
+        assert_eq!(error.details()["message"], "Denied");
+        // The modeled `message` field stands in for `_message`.
+        assert_eq!(error.message(), Some("Denied"));
+        let modeled = error.downcast::<generated::UnauthorizedError>().unwrap();
+        assert_eq!(modeled.message.as_deref(), Some("Denied"));
+
+        // Unknown error codes and plain string payloads.
+        let headers = Headers::new(1, MessageType::ApplicationError, MessageFlags::none());
+        let bytes = Message::new(headers.clone(), Some(json!({"_errorCode": "NewError"})))
+            .to_bytes()
+            .unwrap();
+        let Err(Error::Application(error)) = Message::<()>::from_bytes(&mut &bytes[..]) else {
+            panic!("Expected an application error");
+        };
+        assert_eq!(error.code(), &ErrorCode::Other("NewError".into()));
+        let bytes = Message::new(headers, Some("Oops")).to_bytes().unwrap();
+        let Err(Error::Application(error)) = Message::<()>::from_bytes(&mut &bytes[..]) else {
+            panic!("Expected an application error");
+        };
+        assert_eq!(error.code(), &ErrorCode::ServiceError);
+        assert_eq!(error.message(), Some("Oops"));
+    }
+
+    #[test]
+    fn parse_server_errors() {
+        let mut headers = Headers::new(3, MessageType::ProtocolError, MessageFlags::none());
+        headers.set_operation("aws.greengrass#Nope");
+        let payload = json!({"message": "Unknown operation"});
+        let bytes = Message::new(headers, Some(payload)).to_bytes().unwrap();
+        let res = Message::<()>::from_bytes(&mut &bytes[..]);
+        assert!(
+            matches!(&res, Err(Error::Protocol(e)) if e == "Unknown operation (operation: aws.greengrass#Nope)"),
+            "{res:?}"
+        );
+
+        let headers = Headers::new(0, MessageType::InternalError, MessageFlags::none());
+        let bytes = Message::<()>::new(headers, None).to_bytes().unwrap();
+        let res = Message::<()>::from_bytes(&mut &bytes[..]);
+        assert!(
+            matches!(&res, Err(Error::InternalServer(e)) if e == "No details provided by the server"),
+            "{res:?}"
+        );
+    }
+
+    #[test]
+    fn headers_keep_their_order() {
+        let message = Message::ipc_call("model", "operation", 7, Some(json!({})));
+        let names: Vec<_> = message.headers().iter().map(|(name, _)| name).collect();
+        assert_eq!(
+            names,
+            [":message-type", ":message-flags", ":stream-id", "service-model-type", "operation"]
+        );
+
+        // Encoding is deterministic and survives a round trip.
+        let bytes = message.to_bytes().unwrap();
+        assert_eq!(message.to_bytes().unwrap(), bytes);
+        let decoded = Message::<serde_json::Value>::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn duplicate_headers() {
+        let mut headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        headers.insert("a", headers::Value::Int32(1)).unwrap();
+        headers.insert("b", headers::Value::Int32(2)).unwrap();
+        headers.insert("a", headers::Value::Int32(3)).unwrap();
+        let values: Vec<_> = headers.iter().skip(3).map(|(n, v)| (n, v.as_int32())).collect();
+        assert_eq!(values, [("a", Some(3)), ("b", Some(2))]);
+
+        // On the wire, the last value wins.
+        let mut bytes = Vec::new();
+        headers.write_as_bytes(&mut bytes).unwrap();
+        let mut duplicate = Vec::new();
+        Headers::new(1, MessageType::Application, MessageFlags::none())
+            .write_as_bytes(&mut duplicate)
+            .unwrap();
+        bytes.extend_from_slice(&duplicate);
+        let mut later = Headers::new(9, MessageType::Application, MessageFlags::none());
+        later.insert("b", headers::Value::Int32(4)).unwrap();
+        later.write_as_bytes(&mut bytes).unwrap();
+        let decoded = Headers::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(decoded.stream_id(), 9);
+        assert_eq!(decoded.get("a").and_then(|v| v.as_int32()), Some(3));
+        assert_eq!(decoded.get("b").and_then(|v| v.as_int32()), Some(4));
+        assert_eq!(decoded.iter().count(), 5);
+    }
+
+    #[test]
+    fn maximal_header_blocks() {
+        // As many distinct headers as fit in the size limit, followed by as many repeated ones.
+        // Decoding must stay linear in the number of headers, or a single frame could stall the
+        // connection.
+        let mut bytes = Vec::new();
+        Headers::new(1, MessageType::Application, MessageFlags::none())
+            .write_as_bytes(&mut bytes)
+            .unwrap();
+        let (mut distinct, mut repeated) = (0, 0);
+        while bytes.len() + 6 <= MAX_HEADERS_SIZE / 2 {
+            bytes.push(4);
+            bytes.extend_from_slice(format!("{distinct:04x}").as_bytes());
+            bytes.push(1);
+            distinct += 1;
+        }
+        while bytes.len() + 6 <= MAX_HEADERS_SIZE {
+            bytes.extend_from_slice(b"\x04same\x01");
+            repeated += 1;
+        }
+        assert!(distinct > 10_000 && repeated > 10_000, "{distinct} {repeated}");
+
+        let start = std::time::Instant::now();
+        let headers = Headers::from_bytes(&mut &bytes[..]).unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(headers.iter().count(), 3 + distinct + 1);
+        assert_eq!(headers.get("0000"), Some(&headers::Value::Bool(true)));
+        assert_eq!(headers.get("same"), Some(&headers::Value::Bool(true)));
+        assert!(elapsed < Duration::from_secs(1), "decoding took {elapsed:?}");
+    }
+
+    #[test]
+    fn header_accessors() {
+        let mut headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        assert_eq!(headers.content_type(), None);
+        headers.set_content_type("application/json");
+        headers.set_content_type("text/plain");
+        assert_eq!(headers.content_type(), Some("text/plain"));
+        headers.set_version("0.1.0");
+        assert_eq!(headers.version(), Some("0.1.0"));
+        headers.set_operation("aws.greengrass#UpdateState");
+        assert_eq!(headers.operation(), Some("aws.greengrass#UpdateState"));
+        headers.set_service_model_type("aws.greengrass#UpdateStateRequest");
+        assert_eq!(headers.service_model_type(), Some("aws.greengrass#UpdateStateRequest"));
+
+        // Lookup by a runtime name.
+        let name = String::from("operation");
+        assert_eq!(headers.get(&name).and_then(|v| v.as_str()), Some("aws.greengrass#UpdateState"));
+
+        // Reserved headers can't be overwritten.
+        let res = headers.insert(":stream-id", headers::Value::Int32(2));
+        assert!(matches!(res, Err(Error::InvalidHeader(_))), "{res:?}");
+        let res = headers.insert(":content-type", headers::Value::String("text/html".into()));
+        assert!(matches!(res, Err(Error::InvalidHeader(_))), "{res:?}");
+        assert_eq!(headers.stream_id(), 1);
+        assert_eq!(headers.content_type(), Some("text/plain"));
+        headers.insert(":custom", headers::Value::Bool(true)).unwrap();
+
+        // Names must fit in 255 bytes.
+        headers.insert("x".repeat(255), headers::Value::Bool(true)).unwrap();
+        let res = headers.insert("x".repeat(256), headers::Value::Bool(true));
+        assert!(matches!(res, Err(Error::InvalidHeader(_))), "{res:?}");
+    }
+
+    #[test]
+    fn size_limits() {
+        assert!(Prelude::new(MAX_MESSAGE_SIZE, MAX_HEADERS_SIZE).is_ok());
+        let res = Prelude::new(MAX_MESSAGE_SIZE + 1, 0);
+        assert!(matches!(res, Err(Error::FrameTooLarge { .. })), "{res:?}");
+        let res = Prelude::new(MAX_HEADERS_SIZE * 2, MAX_HEADERS_SIZE + 1);
+        assert!(matches!(res, Err(Error::FrameTooLarge { .. })), "{res:?}");
+
+        // Oversized preludes are rejected when decoding, after validating their checksum.
+        let mut bytes = Vec::new();
+        Prelude::new(MAX_MESSAGE_SIZE, 0).unwrap().write_as_bytes(&mut bytes).unwrap();
+        bytes[3] += 1;
+        let res = Prelude::from_bytes(&mut &bytes[..]);
+        assert!(matches!(res, Err(Error::ChecksumMismatch)), "{res:?}");
+        let checksum = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(&bytes[..8]);
+        bytes[8..].copy_from_slice(&checksum.to_be_bytes());
+        let res = Prelude::from_bytes(&mut &bytes[..]);
+        assert!(
+            matches!(res, Err(Error::FrameTooLarge { total_len, headers_len: 0 }) if total_len == MAX_MESSAGE_SIZE + 1),
+            "{res:?}"
+        );
+
+        // Encoding enforces the same limits.
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let message = Message::new(headers, Some("x".repeat(MAX_MESSAGE_SIZE)));
+        let res = message.to_bytes();
+        assert!(matches!(res, Err(Error::FrameTooLarge { .. })), "{res:?}");
+    }
+
+    #[test]
+    fn truncated_messages() {
+        let bytes = message_with_all_value_types().to_bytes().unwrap();
+
+        for len in 0..bytes.len() {
+            let res = Message::<serde_json::Value>::from_bytes(&mut &bytes[..len]);
+            assert!(res.is_err(), "decoded a message truncated to {len} bytes: {res:?}");
+        }
+        let mut all_bytes = &bytes[..];
+        Message::<serde_json::Value>::from_bytes(&mut all_bytes).unwrap();
+        assert!(all_bytes.is_empty());
+
+        let headers = &bytes[prelude::SIZE..bytes.len() - 4 - r#"{"hello":"world"}"#.len()];
+        for len in 0..headers.len() {
+            // Truncating at a header boundary yields valid headers, anything else doesn't.
+            let _ = Headers::from_bytes(&mut &headers[..len]);
+            let _ = Value::from_bytes(&mut &headers[len..]);
+        }
+        Headers::from_bytes(&mut &headers[..]).unwrap();
+    }
+
+    #[test]
+    fn mutated_messages() {
+        let bytes = message_with_all_value_types().to_bytes().unwrap();
+
+        for i in 0..bytes.len() {
+            for bit in 0..8 {
+                let mut mutated = bytes.clone();
+                mutated[i] ^= 1 << bit;
+                let res = Message::<serde_json::Value>::from_bytes(&mut &mutated[..]);
+                assert!(res.is_err(), "decoded a message with byte {i} mutated: {res:?}");
+
+                // With valid checksums, the mutation has to be caught (or not) by the parsing.
+                fix_checksums(&mut mutated);
+                let _ = Message::<serde_json::Value>::from_bytes(&mut &mutated[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn arbitrary_messages_roundtrip() {
+        let mut rng = fastrand::Rng::with_seed(0x6772_6565_6e67_7261);
+        for _ in 0..1000 {
+            let data: Vec<u8> = (0..rng.usize(0..4096)).map(|_| rng.u8(..)).collect();
+            let mut u = Unstructured::new(&data);
+            let Ok(message) = arbitrary_message(&mut u) else {
+                continue;
+            };
+
+            let bytes = message.to_bytes().unwrap();
+            let mut decoded_bytes = &bytes[..];
+            let decoded = Message::from_bytes(&mut decoded_bytes).unwrap();
+            assert_eq!(decoded, message);
+            assert!(decoded_bytes.is_empty());
+        }
+    }
+
+    #[test]
+    fn arbitrary_bytes_never_panic() {
+        let mut rng = fastrand::Rng::with_seed(0x7361_6664_6563_6f64);
+        for _ in 0..10_000 {
+            let mut data: Vec<u8> = (0..rng.usize(0..512)).map(|_| rng.u8(..)).collect();
+            let _ = Value::from_bytes(&mut &data[..]);
+            let _ = Headers::from_bytes(&mut &data[..]);
+            let _ = Prelude::from_bytes(&mut &data[..]);
+            let _ = Message::<serde_json::Value>::from_bytes(&mut &data[..]);
+
+            // Random bytes hardly ever have valid checksums, so also try with fixed ones.
+            if data.len() >= prelude::SIZE {
+                let len = data.len() as u32;
+                data[..4].copy_from_slice(&len.to_be_bytes());
+                fix_checksums(&mut data);
+                let _ = Message::<serde_json::Value>::from_bytes(&mut &data[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn codec_splits_messages() {
+        let first = message_with_all_value_types().to_bytes().unwrap();
+        let second =
+            Message::<()>::new(Headers::new(7, MessageType::Ping, MessageFlags::none()), None)
+                .to_bytes()
+                .unwrap();
+        let stream = [&first[..], &second[..]].concat();
+
+        // Feed the bytes one at a time, as if they trickled in from the socket.
+        let mut codec = MessageCodec::new();
+        let mut buffer = BytesMut::new();
+        let mut frames = Vec::new();
+        for byte in stream {
+            buffer.extend_from_slice(&[byte]);
+            if let Some(decoded) = codec.decode_frame(&mut buffer).unwrap() {
+                let Decoded::Frame(frame) = decoded else {
+                    panic!("unexpected {decoded:?}");
+                };
+                frames.push(frame);
+            }
+        }
+        assert_eq!(frames, [&first[..], &second[..]]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn codec_skips_oversized_messages() {
+        let headers = Headers::new(3, MessageType::Application, MessageFlags::none());
+        let mut headers_bytes = Vec::new();
+        headers.write_as_bytes(&mut headers_bytes).unwrap();
+        let total_len = MAX_MESSAGE_SIZE + 1;
+        let mut prelude_bytes = vec![0; prelude::SIZE];
+        prelude_bytes[..4].copy_from_slice(&(total_len as u32).to_be_bytes());
+        prelude_bytes[4..8].copy_from_slice(&(headers_bytes.len() as u32).to_be_bytes());
+        fix_checksums(&mut prelude_bytes);
+        let next = message_with_all_value_types().to_bytes().unwrap();
+
+        let mut codec = MessageCodec::new();
+        let mut buffer = BytesMut::from(&prelude_bytes[..]);
+        assert!(codec.decode_frame(&mut buffer).unwrap().is_none());
+        buffer.extend_from_slice(&headers_bytes);
+        let decoded = codec.decode_frame(&mut buffer).unwrap();
+        assert!(
+            matches!(
+                decoded,
+                Some(Decoded::TooLarge { total_len: len, stream_id: Some(3), .. }) if len == total_len
+            ),
+            "{decoded:?}"
+        );
+
+        // The rest of the message is dropped as it comes in, without being buffered.
+        let mut remaining = total_len - prelude_bytes.len() - headers_bytes.len();
+        let chunk = vec![0; 64 * 1024];
+        while remaining > 0 {
+            let len = remaining.min(chunk.len());
+            buffer.extend_from_slice(&chunk[..len]);
+            remaining -= len;
+            assert!(codec.decode_frame(&mut buffer).unwrap().is_none());
+            assert!(buffer.is_empty());
+        }
+        buffer.extend_from_slice(&next);
+        let decoded = codec.decode_frame(&mut buffer).unwrap();
+        assert!(matches!(decoded, Some(Decoded::Frame(frame)) if frame == next[..]));
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn tokio_util_codec() {
+        use tokio_util::codec::{Decoder, Encoder};
+
+        let message = message_with_all_value_types();
+        let mut codec = MessageCodec::new();
+        let mut buffer = BytesMut::new();
+        codec.encode(message.clone(), &mut buffer).unwrap();
+        codec.encode(message.clone(), &mut buffer).unwrap();
+
+        for _ in 0..2 {
+            let decoded = codec.decode(&mut buffer).unwrap().unwrap();
+            assert_eq!(decoded.message().unwrap(), message);
+        }
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+    }
+
+    #[test]
+    fn owned_message() {
+        let message = message_with_all_value_types();
+        let bytes = Bytes::from(message.to_bytes().unwrap());
+
+        let owned = OwnedMessage::from_bytes(bytes.clone()).unwrap();
+        assert_eq!(owned.stream_id(), 42);
+        assert_eq!(owned.message_type(), MessageType::Application);
+        assert_eq!(owned.headers(), *message.headers());
+        assert_eq!(&owned.payload()[..], br#"{"hello":"world"}"#);
+        // The payload is a view into the original bytes, not a copy.
+        assert_eq!(owned.payload().as_ptr(), bytes[bytes.len() - 4 - 17..].as_ptr());
+
+        // The message can outlive the bytes it was parsed from and move across tasks.
+        drop(bytes);
+        let owned = std::thread::spawn(move || owned).join().unwrap();
+        assert_eq!(owned.message::<serde_json::Value>().unwrap(), message);
+
+        let mut bytes = message.to_bytes().unwrap();
+        let len = bytes.len();
+        bytes[len - 1] ^= 1;
+        let res = OwnedMessage::from_bytes(bytes.into());
+        assert!(matches!(res, Err(Error::ChecksumMismatch)), "{res:?}");
+    }
+
+    #[test]
+    fn content_types() {
+        let headers = || Headers::new(1, MessageType::Application, MessageFlags::none());
+
+        // JSON is the default, so no header is added for it.
+        let bytes = Message::new(headers(), Some(json!([1, 2]))).to_bytes().unwrap();
+        let message = Message::<serde_json::Value>::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(message.headers().content_type(), None);
+        assert_eq!(message.payload(), Some(&json!([1, 2])));
+
+        let binary = Binary::new(&[0xff, 0x00, 0x7f][..]);
+        let bytes = Message::new(headers(), Some(binary.clone())).to_bytes().unwrap();
+        let message = Message::<Binary<'_>>::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(message.headers().content_type(), Some(BINARY_CONTENT_TYPE));
+        assert_eq!(message.payload().unwrap().bytes(), binary.bytes());
+        let res = Message::<serde_json::Value>::from_bytes(&mut &bytes[..]);
+        assert!(matches!(res, Err(Error::Protocol(_))), "{res:?}");
+        let res = Message::<Text<'_>>::from_bytes(&mut &bytes[..]);
+        assert!(matches!(res, Err(Error::Protocol(_))), "{res:?}");
+
+        let mut text_headers = headers();
+        text_headers.set_content_type("text/plain; charset=utf-8");
+        let bytes = Message::new(text_headers, Some(Text::new("héllo"))).to_bytes().unwrap();
+        let message = Message::<Text<'_>>::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(message.payload().unwrap().as_str(), "héllo");
+        // Anything can be read as binary.
+        let message = Message::<Binary<'_>>::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(message.payload().unwrap().bytes(), "héllo".as_bytes());
+
+        let mut json_headers = headers();
+        json_headers.set_content_type(JSON_CONTENT_TYPE);
+        let res = Message::new(json_headers, Some(Text::new("hello"))).to_bytes();
+        assert!(matches!(res, Err(Error::InvalidHeader(_))), "{res:?}");
+    }
+
+    #[test]
+    fn custom_payload_codec() {
+        #[derive(Debug, PartialEq)]
+        struct Csv(Vec<String>);
+
+        impl EncodePayload for Csv {
+            fn content_type(&self) -> &str {
+                "text/csv"
+            }
+
+            fn encode(&self, writer: &mut impl Write) -> crate::Result<()> {
+                writer.write_all(self.0.join(",").as_bytes()).map_err(Into::into)
+            }
+        }
+
+        impl DecodePayload<'_> for Csv {
+            fn accepts(content_type: &str) -> bool {
+                content_type == "text/csv"
+            }
+
+            fn decode(bytes: &[u8]) -> crate::Result<Self> {
+                let text = core::str::from_utf8(bytes)
+                    .map_err(|_| Error::Protocol("Invalid CSV".into()))?;
+
+                Ok(Self(text.split(',').map(Into::into).collect()))
+            }
+        }
+
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let csv = Csv(vec!["a".into(), "b".into()]);
+        let bytes = Message::new(headers, Some(csv)).to_bytes().unwrap();
+        let message = Message::<Csv>::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(message.headers().content_type(), Some("text/csv"));
+        assert_eq!(message.payload().unwrap().0, ["a", "b"]);
+    }
+
+    #[test]
+    fn encode_into_buffers() {
+        let message = message_with_all_value_types();
+        let bytes = message.to_bytes().unwrap();
+        assert_eq!(message.encoded_len().unwrap(), bytes.len());
+
+        // Messages are appended, so a buffer can be reused.
+        let mut buffer = BytesMut::new();
+        assert_eq!(message.encode_into(&mut buffer).unwrap(), bytes.len());
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let binary = Message::new(headers, Some(Binary::new(&b"\0\x01"[..])));
+        let len = binary.encode_into(&mut buffer).unwrap();
+        assert_eq!(&buffer[..bytes.len()], &bytes[..]);
+        assert_eq!(&buffer[bytes.len()..], &binary.to_bytes().unwrap()[..]);
+        assert_eq!(buffer.len(), bytes.len() + len);
+
+        // A fixed-size buffer that's too small is an error, not a panic.
+        let mut small = [0; 32];
+        let res = message.encode_into(&mut &mut small[..]);
+        assert!(matches!(res, Err(Error::Io(_))), "{res:?}");
+        let mut exact = vec![0; bytes.len()];
+        message.encode_into(&mut &mut exact[..]).unwrap();
+        assert_eq!(exact, bytes);
+
+        // The payload is encoded once per message, and a failure leaves the buffer as it was.
+        #[derive(Debug)]
+        struct Counted<'c> {
+            encoded: &'c Cell<usize>,
+            fail: bool,
+        }
+
+        impl EncodePayload for Counted<'_> {
+            fn content_type(&self) -> &str {
+                JSON_CONTENT_TYPE
+            }
+
+            fn encode(&self, writer: &mut impl Write) -> crate::Result<()> {
+                self.encoded.set(self.encoded.get() + 1);
+                writer.write_all(b"{}")?;
+                if self.fail {
+                    return Err(Error::Protocol("Failed to encode".into()));
+                }
+
+                Ok(())
+            }
+        }
+
+        let encoded = Cell::new(0);
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let counted = Message::new(headers, Some(Counted { encoded: &encoded, fail: false }));
+        counted.to_bytes().unwrap();
+        assert_eq!(encoded.get(), 1);
+        counted.encode_into(&mut buffer).unwrap();
+        assert_eq!(encoded.get(), 2);
+
+        let len = buffer.len();
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let failing = Message::new(headers, Some(Counted { encoded: &encoded, fail: true }));
+        assert!(failing.encode_into(&mut buffer).is_err());
+        assert_eq!(buffer.len(), len);
+        let mut vec = bytes.clone();
+        assert!(failing.encode_appending(&mut vec).is_err());
+        assert_eq!(vec, bytes);
+    }
+
+    #[test]
+    fn json_payloads() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Payload<'p> {
+            borrowed: &'p str,
+            owned: String,
+            float: f64,
+            nested: Vec<Option<u8>>,
+        }
+
+        // Whatever the JSON backend, the wire output is the same as with `serde_json`, and types
+        // borrowing from the payload can be decoded.
+        let payload = Payload {
+            borrowed: "borrowed",
+            owned: "\"escaped\" \u{e9}".into(),
+            float: 0.1 + 0.2,
+            nested: vec![Some(1), None],
+        };
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let bytes = Message::new(headers, Some(&payload)).to_bytes().unwrap();
+        let json = serde_json::to_vec(&payload).unwrap();
+        assert_eq!(&bytes[bytes.len() - 4 - json.len()..bytes.len() - 4], &json[..]);
+
+        let message = Message::<Payload<'_>>::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(message.payload(), Some(&payload));
+        let message = Message::<serde_json::Value>::from_bytes(&mut &bytes[..]).unwrap();
+        assert_eq!(message.payload(), Some(&serde_json::to_value(&payload).unwrap()));
+        // Owned types are decoded with the selected backend.
+        let owned = OwnedMessage::from_bytes(Bytes::from(bytes)).unwrap();
+        let message = owned.message_owned::<serde_json::Value>().unwrap();
+        assert_eq!(message.payload(), Some(&serde_json::to_value(&payload).unwrap()));
+        assert_eq!(message.payload().unwrap()["float"], 0.1 + 0.2);
+
+        // Invalid JSON is an error either way.
+        let headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let invalid = Binary::new(&b"{\"owned\":"[..]).with_content_type(JSON_CONTENT_TYPE);
+        let bytes = Message::new(headers, Some(invalid)).to_bytes().unwrap();
+        let res = Message::<serde_json::Value>::from_bytes(&mut &bytes[..]);
+        assert!(matches!(res, Err(Error::Json(_))), "{res:?}");
+        let owned = OwnedMessage::from_bytes(Bytes::from(bytes)).unwrap();
+        let res = owned.message_owned::<serde_json::Value>();
+        #[cfg(not(feature = "simd-json"))]
+        assert!(matches!(res, Err(Error::Json(_))), "{res:?}");
+        #[cfg(feature = "simd-json")]
+        assert!(matches!(res, Err(Error::SimdJson(_))), "{res:?}");
+    }
+
+    #[test]
+    fn value_conversions() {
+        let time =
+            UNIX_EPOCH + Duration::from_millis(1_700_000_000_123) + Duration::from_micros(456);
+        let value = Value::from(time);
+        assert_eq!(value, Value::Timestamp(1_700_000_000_123));
+        assert_eq!(value.to_string(), "2023-11-14T22:13:20.123Z");
+        let back = SystemTime::try_from(&value).unwrap();
+        assert_eq!(back, UNIX_EPOCH + Duration::from_millis(1_700_000_000_123));
+
+        // Times before the epoch round down.
+        let value = Value::from(UNIX_EPOCH - Duration::from_micros(1500));
+        assert_eq!(value, Value::Timestamp(-2));
+        assert_eq!(value.to_string(), "1969-12-31T23:59:59.998Z");
+        assert_eq!(Value::Timestamp(0).to_string(), "1970-01-01T00:00:00.000Z");
+        assert_eq!(Value::Timestamp(951_782_400_000).to_string(), "2000-02-29T00:00:00.000Z");
+        // Far out of range for `SystemTime`, but formatting still works.
+        let _ = Value::Timestamp(i64::MIN).to_string();
+
+        let mut headers = Headers::new(1, MessageType::Application, MessageFlags::none());
+        let uuid = uuid::Uuid::from_u128(0x1234);
+        headers.insert("bool", true).unwrap();
+        headers.insert("byte", 7u8).unwrap();
+        headers.insert("int16", -16i16).unwrap();
+        headers.insert("int64", 64i64).unwrap();
+        headers.insert("bytes", &[0xde, 0xad][..]).unwrap();
+        headers.insert("string", "hello").unwrap();
+        headers.insert("owned", String::from("world")).unwrap();
+        headers.insert("time", time).unwrap();
+        headers.insert("uuid", uuid).unwrap();
+
+        let get = |name| headers.get(name).unwrap();
+        assert!(bool::try_from(get("bool")).unwrap());
+        assert_eq!(u8::try_from(get("byte")).unwrap(), 7);
+        assert_eq!(i16::try_from(get("int16")).unwrap(), -16);
+        assert_eq!(i64::try_from(get("int64")).unwrap(), 64);
+        assert_eq!(<&[u8]>::try_from(get("bytes")).unwrap(), [0xde, 0xad]);
+        assert_eq!(<&str>::try_from(get("string")).unwrap(), "hello");
+        assert_eq!(<&str>::try_from(get("owned")).unwrap(), "world");
+        assert_eq!(uuid::Uuid::try_from(get("uuid")).unwrap(), uuid);
+        let res = i32::try_from(get("int64"));
+        assert!(matches!(&res, Err(Error::InvalidHeader(e)) if e == "expected int32, got int64"));
+        let res = SystemTime::try_from(get("string"));
+        assert!(matches!(res, Err(Error::InvalidHeader(_))), "{res:?}");
+
+        let display: Vec<_> = headers.iter().skip(3).map(|(_, v)| v.to_string()).collect();
+        assert_eq!(
+            display,
+            [
+                "true",
+                "7",
+                "-16",
+                "64",
+                "0xdead",
+                "hello",
+                "world",
+                "2023-11-14T22:13:20.123Z",
+                "00000000-0000-0000-0000-000000001234",
+            ]
+        );
+    }
+
+    #[test]
+    fn value_conversion_errors() {
+        let error = |res: crate::Result<()>| match res {
+            Err(Error::InvalidHeader(e)) => e,
+            res => panic!("Unexpected result: {res:?}"),
+        };
+        let bytes = Value::ByteBuffer(Cow::Borrowed(&[0xde, 0xad]));
+        let e = error(uuid::Uuid::try_from(&bytes).map(drop));
+        assert_eq!(e, "expected UUID, got byte buffer");
+        let e = error(SystemTime::try_from(&Value::String("now".into())).map(drop));
+        assert_eq!(e, "expected timestamp, got string");
+
+        // Only a timestamp converted to a time can be out of range.
+        let e = error(i64::try_from(&Value::Timestamp(0)).map(drop));
+        assert_eq!(e, "expected int64, got timestamp");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_timestamps() {
+        use chrono::{DateTime, TimeZone, Utc};
+
+        let time = Utc.timestamp_millis_opt(-1_234).unwrap();
+        let value = Value::from(time);
+        assert_eq!(value, Value::Timestamp(-1_234));
+        assert_eq!(DateTime::<Utc>::try_from(&value).unwrap(), time);
+        let res = DateTime::<Utc>::try_from(&Value::Timestamp(i64::MAX));
+        let expected = format!("timestamp {} is out of range", i64::MAX);
+        assert!(matches!(&res, Err(Error::InvalidHeader(e)) if *e == expected), "{res:?}");
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_timestamps() {
+        use time::{OffsetDateTime, UtcOffset};
+
+        let time = OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_456_789)
+            .unwrap()
+            .to_offset(UtcOffset::from_hms(1, 0, 0).unwrap());
+        let value = Value::from(time);
+        assert_eq!(value, Value::Timestamp(1_700_000_000_123));
+        let back = OffsetDateTime::try_from(&value).unwrap();
+        assert_eq!(back.unix_timestamp_nanos(), 1_700_000_000_123_000_000);
+        assert_eq!(back.offset(), UtcOffset::UTC);
+        assert!(OffsetDateTime::try_from(&Value::Timestamp(i64::MAX)).is_err());
+    }
+
+    fn message_with_all_value_types() -> Message<'static, serde_json::Value> {
+        let mut headers = Headers::new(42, MessageType::Application, MessageFlags::none());
+        headers.insert("bool", Value::Bool(true)).unwrap();
+        headers.insert("byte", Value::Byte(7)).unwrap();
+        headers.insert("int16", Value::Int16(-16)).unwrap();
+        headers.insert("int64", Value::Int64(1 << 40)).unwrap();
+        headers.insert("buffer", Value::ByteBuffer(Cow::Borrowed(&[1, 2, 3]))).unwrap();
+        headers.insert("timestamp", Value::Timestamp(1_700_000_000_000)).unwrap();
+        headers.insert("uuid", Value::Uuid(uuid::Uuid::from_u128(42))).unwrap();
+        headers.set_content_type("application/json");
+
+        Message::new(headers, Some(json!({"hello": "world"})))
+    }
+
+    fn arbitrary_message<'a>(
+        u: &mut Unstructured<'a>,
+    ) -> arbitrary::Result<Message<'a, serde_json::Value>> {
+        let flags = MessageFlags::from_bits_truncate(u.arbitrary::<u32>()?);
+        let mut headers = Headers::new(u.arbitrary()?, MessageType::Application, flags);
+        for _ in 0..u.int_in_range(0..=16)? {
+            let name: &str = u.arbitrary()?;
+            let value = match u.int_in_range(0..=8)? {
+                0 => Value::Bool(u.arbitrary()?),
+                1 => Value::Byte(u.arbitrary()?),
+                2 => Value::Int16(u.arbitrary()?),
+                3 => Value::Int32(u.arbitrary()?),
+                4 => Value::Int64(u.arbitrary()?),
+                5 => Value::ByteBuffer(Cow::Borrowed(u.arbitrary()?)),
+                6 => Value::String(Cow::Borrowed(u.arbitrary()?)),
+                7 => Value::Timestamp(u.arbitrary()?),
+                _ => Value::Uuid(uuid::Uuid::from_u128(u.arbitrary()?)),
+            };
+            // Reserved and overlong names are rejected, which is fine here.
+            let _ = headers.insert(name, value);
+        }
+        let payload = u.arbitrary::<Option<String>>()?.map(serde_json::Value::String);
+
+        Ok(Message::new(headers, payload))
+    }
+
+    /// Recompute both checksums of an encoded message.
+    fn fix_checksums(bytes: &mut [u8]) {
+        let crc32 = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+        let checksum = crc32.checksum(&bytes[..8]);
+        bytes[8..12].copy_from_slice(&checksum.to_be_bytes());
+        let len = bytes.len();
+        if len >= prelude::SIZE + 4 {
+            let checksum = crc32.checksum(&bytes[..len - 4]);
+            bytes[len - 4..].copy_from_slice(&checksum.to_be_bytes());
+        }
+    }
 }
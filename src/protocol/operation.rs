@@ -0,0 +1,30 @@
+use core::fmt::Debug;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::ErrorCode;
+
+/// An IPC operation, describing the types involved in calling it.
+///
+/// Call an operation through [`crate::Connection::invoke`], or
+/// [`crate::Connection::subscribe`] if it's a [`StreamingOperation`].
+pub trait Operation {
+    /// The name of the operation, sent in the `operation` header.
+    const NAME: &'static str;
+    /// The model type of the request, sent in the `service-model-type` header.
+    const REQUEST_MODEL_TYPE: &'static str;
+    /// The errors the service may return, as the [`ErrorCode`] of an
+    /// [`crate::Error::Application`].
+    const ERRORS: &'static [ErrorCode];
+
+    /// The payload of the request.
+    type Request<'r>: Serialize + Debug;
+    /// The payload of the response.
+    type Response: DeserializeOwned + Debug;
+}
+
+/// An operation that opens a stream of events.
+pub trait StreamingOperation: Operation {
+    /// The payload of the events.
+    type Event: DeserializeOwned + Debug;
+}
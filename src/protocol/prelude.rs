@@ -1,9 +1,11 @@
 //! The prelude is a part of the message that is used to determine the length of the message and
 //! headers.
 
-use crc::{Crc, CRC_32_ISO_HDLC};
 use endi::{ReadBytes, WriteBytes};
-use std::io::{self, Write};
+use std::io::Write;
+
+use super::CRC32;
+use crate::{Error, Result};
 
 /// The prelude.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -15,44 +17,56 @@ pub struct Prelude {
 
 impl Prelude {
     /// Create a new prelude.
-    pub fn new(total_len: usize, headers_len: usize) -> io::Result<Self> {
-        let total_len = total_len
-            .try_into()
-            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid length"))?;
-        let headers_len = headers_len
-            .try_into()
-            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid length"))?;
+    ///
+    /// Fails with [`Error::FrameTooLarge`] if the message exceeds [`MAX_MESSAGE_SIZE`] or its
+    /// headers exceed [`MAX_HEADERS_SIZE`].
+    pub fn new(total_len: usize, headers_len: usize) -> Result<Self> {
+        check_limits(total_len, headers_len)?;
+        if total_len < SIZE + 4 || headers_len > total_len - SIZE - 4 {
+            return Err(Error::Protocol("Invalid length".into()));
+        }
+        // Can't fail, `check_limits` ensures both fit.
+        let total_len = total_len as u32;
+        let headers_len = headers_len as u32;
         let mut bytes = [0; 8];
         let mut bytes_buf = &mut bytes[..];
         bytes_buf.write_u32(endi::Endian::Big, total_len).unwrap();
         bytes_buf.write_u32(endi::Endian::Big, headers_len).unwrap();
-        let crc32 = Crc::<u32>::new(&CRC_32_ISO_HDLC);
-        let crc_checksum = crc32.checksum(&bytes);
+        let crc_checksum = CRC32.checksum(&bytes);
 
         Ok(Self { total_len: total_len as usize, headers_len: headers_len as usize, crc_checksum })
     }
 
     /// Parse a prelude from bytes.
-    pub fn from_bytes(bytes: &mut &[u8]) -> io::Result<Self> {
-        let crc32 = Crc::<u32>::new(&CRC_32_ISO_HDLC);
-        let prelude_checksum = crc32.checksum(&bytes[..8]);
-        let total_len = bytes.read_u32(endi::Endian::Big)?;
-        let headers_len = bytes.read_u32(endi::Endian::Big)?;
-        let crc_checksum = bytes.read_u32(endi::Endian::Big)?;
-
-        if headers_len > total_len {
-            return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid length"));
-        }
+    ///
+    /// Fails with [`Error::FrameTooLarge`] if the message exceeds [`MAX_MESSAGE_SIZE`] or its
+    /// headers exceed [`MAX_HEADERS_SIZE`]. Since the prelude itself is valid in that case, the
+    /// message can be skipped to get to the next one. Any other invalid input results in an error
+    /// as well, never a panic.
+    pub fn from_bytes(bytes: &mut &[u8]) -> Result<Self> {
+        let invalid = |_| Error::Protocol("Invalid prelude".into());
+        let lengths = bytes.get(..8).ok_or(Error::Protocol("Invalid prelude".into()))?;
+        let prelude_checksum = CRC32.checksum(lengths);
+        let total_len = bytes.read_u32(endi::Endian::Big).map_err(invalid)?;
+        let headers_len = bytes.read_u32(endi::Endian::Big).map_err(invalid)?;
+        let crc_checksum = bytes.read_u32(endi::Endian::Big).map_err(invalid)?;
 
         if prelude_checksum != crc_checksum {
-            return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid prelude checksum"));
+            return Err(Error::ChecksumMismatch);
         }
 
+        if (total_len as usize) < SIZE + 4
+            || (headers_len as usize) > (total_len as usize).saturating_sub(SIZE + 4)
+        {
+            return Err(Error::Protocol("Invalid length".into()));
+        }
+        check_limits(total_len as usize, headers_len as usize)?;
+
         Ok(Self { total_len: total_len as usize, headers_len: headers_len as usize, crc_checksum })
     }
 
     /// Write the prelude as bytes.
-    pub fn write_as_bytes(&self, writer: &mut impl Write) -> io::Result<()> {
+    pub fn write_as_bytes(&self, writer: &mut impl Write) -> std::io::Result<()> {
         // Safe to cast because our constructor ensures that the values are within u32 range.
         writer.write_u32(endi::Endian::Big, self.total_len as u32)?;
         writer.write_u32(endi::Endian::Big, self.headers_len as u32)?;
@@ -79,3 +93,17 @@ impl Prelude {
 
 /// The size of the prelude in bytes.
 pub const SIZE: usize = 12;
+
+/// The maximum size of a message in bytes, including the prelude and the trailing checksum.
+pub const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// The maximum size of the headers of a message in bytes.
+pub const MAX_HEADERS_SIZE: usize = 128 * 1024;
+
+fn check_limits(total_len: usize, headers_len: usize) -> Result<()> {
+    if total_len > MAX_MESSAGE_SIZE || headers_len > MAX_HEADERS_SIZE {
+        return Err(Error::FrameTooLarge { total_len, headers_len });
+    }
+
+    Ok(())
+}
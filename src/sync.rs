@@ -0,0 +1,9 @@
+use std::sync::{Mutex, MutexGuard};
+
+/// Lock `mutex`, ignoring poisoning.
+///
+/// The locks of this crate are never held across a panic-prone operation, so poisoning is not a
+/// concern.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
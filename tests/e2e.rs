@@ -1,29 +1,50 @@
 use std::{
     env::{self, temp_dir},
-    future::pending,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
-use enumflags2::BitFlags;
+use crc::{Crc, CRC_32_ISO_HDLC};
 use greengrass_sdk::{
     env::{AUTH_TOKEN_ENV, SOCKET_PATH_ENV},
     protocol::{
         headers::{Headers, MessageFlags, MessageType},
-        ComponentUpdateSubscriptionRequest, ComponentUpdateSubscriptionResponse, ConnectRequest,
-        ConnectResponse, DeferComponentUpdateRequest, DeferComponentUpdateResponse, Message,
-        PreComponentUpdateEvent, RecheckAfterMs, UpdateStateRequest, UpdateStateResponse,
+        prelude::{Prelude, MAX_MESSAGE_SIZE, SIZE},
+        ComponentUpdateSubscriptionResponse, ConnectRequest, ConnectResponse,
+        DeferComponentUpdateRequest, DeferComponentUpdateResponse, Message,
+        PreComponentUpdateEvent, RecheckAfterMs, UpdateState, UpdateStateRequest,
+        UpdateStateResponse,
     },
-    IpcClient, LifecycleState,
+    Connection, DeploymentStage, Error, IpcClient, KeepAlive, LifecycleState,
 };
+use serde::{de::IgnoredAny, Serialize};
+use serde_json::json;
 use test_log::test;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::{UnixListener, UnixStream},
     spawn,
-    sync::broadcast::{channel, Sender},
+    sync::{
+        mpsc::{unbounded_channel, UnboundedSender},
+        oneshot, Notify,
+    },
     task::JoinHandle,
 };
+use uuid::Uuid;
+
+const DEPLOYMENT_ID: &str = "77d00c6b-f0c6-4e14-86cb-d476f0016044";
 
-fn mock_greengrass_server(deferred_notifier: Sender<()>) -> JoinHandle<()> {
+/// A mock server that answers all requests and pings, sending out a pre-update event right after
+/// each component update subscription. The `recheckAfterMs` of each deferral is reported through
+/// `deferrals` and the IDs of the streams terminated by the client through `terminations`.
+fn mock_greengrass_server(
+    deferrals: UnboundedSender<RecheckAfterMs>,
+    terminations: UnboundedSender<i32>,
+) -> JoinHandle<()> {
     let filename = format!("greengrass-sdk-rs-{}", fastrand::f64());
     let path = temp_dir().join(filename);
     env::set_var(SOCKET_PATH_ENV, path.as_os_str());
@@ -31,143 +52,449 @@ fn mock_greengrass_server(deferred_notifier: Sender<()>) -> JoinHandle<()> {
 
     let listener = UnixListener::bind(path).unwrap();
     spawn(async move {
-        let mut client_num = 0;
         while let Ok((mut stream, _)) = listener.accept().await {
-            client_num += 1;
-            let deferred_notifier = deferred_notifier.clone();
+            let deferrals = deferrals.clone();
+            let terminations = terminations.clone();
             spawn(async move {
-                // Handshake
-                let response_headers = Headers::new(
-                    0,
-                    MessageType::ConnectAck,
-                    MessageFlags::ConnectionAccepted.into(),
-                );
-                let mut buf = [0; 1024];
-                let n = stream.read(&mut buf).await.unwrap();
-                let msg: Message<ConnectRequest<'_>> = Message::from_bytes(&mut &buf[..n]).unwrap();
-                assert_eq!(msg.headers().message_type(), MessageType::Connect);
-                assert_eq!(msg.headers().message_flags(), MessageFlags::none());
-                assert_eq!(msg.headers().stream_id(), 0);
-                let auth_token = greengrass_sdk::env::auth_token().unwrap();
-                assert_eq!(msg.payload(), ConnectRequest::new(auth_token).unwrap().payload());
-                let response_bytes =
-                    Message::new(response_headers, None::<ConnectResponse>).to_bytes().unwrap();
-                let _ = stream.write_all(&response_bytes).await;
-
-                if client_num == 1 {
-                    // Receive set component state request.
-                    let response_headers = Headers::new(
-                        1,
-                        MessageType::Application,
-                        MessageFlags::TerminateStream.into(),
-                    );
-                    let response = Message::new(response_headers, Some(UpdateStateResponse {}));
-                    mock_greengrass_server_response(
-                        &mut stream,
-                        1,
-                        MessageType::Application,
-                        MessageFlags::none(),
-                        UpdateStateRequest::new(1, LifecycleState::Running).payload(),
-                        &response.to_bytes().unwrap(),
-                    )
-                    .await;
-                } else {
-                    // Component update subscription is received from a second connection.
-                    let response_headers =
-                        Headers::new(1, MessageType::Application, MessageFlags::none());
-                    let response = Message::new(
-                        response_headers.clone(),
-                        Some(ComponentUpdateSubscriptionResponse::new(None, None)),
-                    );
-                    mock_greengrass_server_response(
-                        &mut stream,
-                        1,
-                        MessageType::Application,
-                        MessageFlags::none(),
-                        ComponentUpdateSubscriptionRequest::new(1).payload(),
-                        &response.to_bytes().unwrap(),
-                    )
-                    .await;
-
-                    // Now send out a component pre-update event.
-                    let deployment_id = "77d00c6b-f0c6-4e14-86cb-d476f0016044".parse().unwrap();
-                    let pre_update_event = PreComponentUpdateEvent::new(deployment_id, false);
-                    let response = Message::new(
-                        response_headers,
-                        Some(ComponentUpdateSubscriptionResponse::new(
-                            Some(pre_update_event),
-                            None,
-                        )),
-                    );
-                    let _ = stream.write_all(&response.to_bytes().unwrap()).await;
-
-                    // Now receive a defer component update request and respond with success.
-                    let response_headers = Headers::new(
-                        2,
-                        MessageType::Application,
-                        MessageFlags::TerminateStream.into(),
-                    );
-
-                    let n = stream.read(&mut buf).await.unwrap();
-                    let msg: Message<DeferComponentUpdateRequest> =
-                        Message::from_bytes(&mut &buf[..n]).unwrap();
-                    assert_eq!(msg.headers().message_type(), MessageType::Application);
-                    assert_eq!(msg.headers().message_flags(), MessageFlags::none());
-                    assert_eq!(msg.headers().stream_id(), 2);
-                    let request = msg.payload().unwrap();
-                    assert_eq!(request.deployment_id(), deployment_id);
-                    assert_eq!(request.component_name(), None);
-                    assert_eq!(
-                        request.recheck_after_ms(),
-                        RecheckAfterMs::Defer(60_000.try_into().unwrap())
-                    );
-
-                    let response =
-                        Message::new(response_headers, Some(DeferComponentUpdateResponse {}));
-                    let _ = stream.write_all(&response.to_bytes().unwrap()).await;
-
-                    deferred_notifier.send(()).unwrap();
-                }
+                handshake(&mut stream, "whatever").await;
+
+                loop {
+                    let frame = read_frame(&mut stream).await;
+                    let headers = Message::<IgnoredAny>::from_bytes(&mut &frame[..])
+                        .unwrap()
+                        .headers()
+                        .to_owned();
+                    if headers.message_type() == MessageType::Ping {
+                        let pong_headers = Headers::new(0, MessageType::Pong, MessageFlags::none());
+                        write_message(&mut stream, pong_headers, None::<()>).await;
+
+                        continue;
+                    }
+                    assert_eq!(headers.message_type(), MessageType::Application);
+                    let stream_id = headers.stream_id();
+                    if headers.message_flags().contains(MessageFlags::TerminateStream) {
+                        terminations.send(stream_id).unwrap();
+
+                        continue;
+                    }
+                    assert_eq!(headers.message_flags(), MessageFlags::none());
+                    let operation = headers.operation().unwrap();
+
+                    match operation {
+                        "aws.greengrass#UpdateState" => {
+                            let msg: Message<UpdateStateRequest> =
+                                Message::from_bytes(&mut &frame[..]).unwrap();
+                            assert_eq!(
+                                msg.payload(),
+                                UpdateStateRequest::new(stream_id, LifecycleState::Running)
+                                    .payload()
+                            );
+                            let response_headers = Headers::new(
+                                stream_id,
+                                MessageType::Application,
+                                MessageFlags::TerminateStream.into(),
+                            );
+                            write_message(
+                                &mut stream,
+                                response_headers,
+                                Some(UpdateStateResponse {}),
+                            )
+                            .await;
+                        }
+                        "aws.greengrass#SubscribeToComponentUpdates" => {
+                            let response_headers = Headers::new(
+                                stream_id,
+                                MessageType::Application,
+                                MessageFlags::none(),
+                            );
+                            write_message(
+                                &mut stream,
+                                response_headers.clone(),
+                                Some(ComponentUpdateSubscriptionResponse::new(None, None)),
+                            )
+                            .await;
+
+                            // Now send out a component pre-update event.
+                            let deployment_id = DEPLOYMENT_ID.parse().unwrap();
+                            let pre_update_event =
+                                PreComponentUpdateEvent::new(deployment_id, false);
+                            write_message(
+                                &mut stream,
+                                response_headers,
+                                Some(ComponentUpdateSubscriptionResponse::new(
+                                    Some(pre_update_event),
+                                    None,
+                                )),
+                            )
+                            .await;
+                        }
+                        "aws.greengrass#DeferComponentUpdate" => {
+                            let msg: Message<DeferComponentUpdateRequest> =
+                                Message::from_bytes(&mut &frame[..]).unwrap();
+                            let request = msg.payload().unwrap();
+                            assert_eq!(
+                                request.deployment_id(),
+                                DEPLOYMENT_ID.parse::<Uuid>().unwrap()
+                            );
+                            assert_eq!(request.component_name(), None);
+
+                            let response_headers = Headers::new(
+                                stream_id,
+                                MessageType::Application,
+                                MessageFlags::TerminateStream.into(),
+                            );
+                            write_message(
+                                &mut stream,
+                                response_headers,
+                                Some(DeferComponentUpdateResponse {}),
+                            )
+                            .await;
 
-                // Not to drop the stream immediately.
-                pending::<()>().await;
+                            deferrals.send(request.recheck_after_ms()).unwrap();
+                        }
+                        operation => panic!("Unexpected operation `{operation}`"),
+                    }
+                }
             });
         }
     })
 }
 
-async fn mock_greengrass_server_response<ReqPayload>(
-    stream: &mut UnixStream,
-    stream_id: i32,
-    request_message_type: MessageType,
-    request_message_flags: BitFlags<MessageFlags>,
-    request_payload: Option<&ReqPayload>,
-    response_bytes: &[u8],
-) where
-    for<'p> ReqPayload: PartialEq + std::fmt::Debug + serde::Deserialize<'p>,
+/// A mock server for a single connection, running `handler` on the stream after the handshake with
+/// `auth_token`. Returns the path of the socket.
+fn mock_server<F, Fut>(auth_token: &'static str, handler: F) -> &'static str
+where
+    F: FnOnce(UnixStream) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
 {
-    let mut buf = [0; 1024];
+    let filename = format!("greengrass-sdk-rs-{}", fastrand::f64());
+    let path = temp_dir().join(filename).to_str().unwrap().to_owned();
+
+    let listener = UnixListener::bind(&path).unwrap();
+    spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        handshake(&mut stream, auth_token).await;
+
+        handler(stream).await;
+    });
+
+    Box::leak(path.into_boxed_str())
+}
 
-    let n = stream.read(&mut buf).await.unwrap();
-    let msg: Message<ReqPayload> = Message::from_bytes(&mut &buf[..n]).unwrap();
-    assert_eq!(msg.headers().message_type(), request_message_type);
-    assert_eq!(msg.headers().message_flags(), request_message_flags);
-    assert_eq!(msg.headers().stream_id(), stream_id);
-    assert_eq!(msg.payload(), request_payload);
+/// A mock server that never answers anything after the handshake. It pings the client once and
+/// reports the pong through `pong`.
+fn unresponsive_server(pong: oneshot::Sender<()>) -> &'static str {
+    mock_server("unresponsive", |mut stream| async move {
+        let ping_headers = Headers::new(0, MessageType::Ping, MessageFlags::none());
+        write_message(&mut stream, ping_headers, None::<()>).await;
+        let mut pong = Some(pong);
+        loop {
+            let frame = read_frame(&mut stream).await;
+            let msg = Message::<IgnoredAny>::from_bytes(&mut &frame[..]).unwrap();
+            if msg.headers().message_type() == MessageType::Pong {
+                if let Some(pong) = pong.take() {
+                    pong.send(()).unwrap();
+                }
+            }
+        }
+    })
+}
+
+/// A mock server that answers the first request with a message exceeding the size limits and the
+/// second one normally.
+fn oversized_server() -> &'static str {
+    mock_server("oversized", |mut stream| async move {
+        let frame = read_frame(&mut stream).await;
+        let msg = Message::<IgnoredAny>::from_bytes(&mut &frame[..]).unwrap();
+        let headers = Headers::new(
+            msg.headers().stream_id(),
+            MessageType::Application,
+            MessageFlags::TerminateStream.into(),
+        );
+        let mut headers_bytes = Vec::new();
+        headers.write_as_bytes(&mut headers_bytes).unwrap();
+        let total_len = MAX_MESSAGE_SIZE + 1;
+        let mut prelude = Vec::new();
+        prelude.extend_from_slice(&(total_len as u32).to_be_bytes());
+        prelude.extend_from_slice(&(headers_bytes.len() as u32).to_be_bytes());
+        let checksum = Crc::<u32>::new(&CRC_32_ISO_HDLC).checksum(&prelude);
+        prelude.extend_from_slice(&checksum.to_be_bytes());
+        stream.write_all(&prelude).await.unwrap();
+        stream.write_all(&headers_bytes).await.unwrap();
+        let rest = total_len - prelude.len() - headers_bytes.len();
+        stream.write_all(&vec![0; rest]).await.unwrap();
 
-    let _ = stream.write_all(response_bytes).await;
+        let frame = read_frame(&mut stream).await;
+        let msg = Message::<IgnoredAny>::from_bytes(&mut &frame[..]).unwrap();
+        let headers = Headers::new(
+            msg.headers().stream_id(),
+            MessageType::Application,
+            MessageFlags::TerminateStream.into(),
+        );
+        write_message(&mut stream, headers, Some(UpdateStateResponse {})).await;
+        let _ = read_frame(&mut stream).await;
+    })
+}
+
+/// A mock server that fails the first request with a protocol error and then fails the whole
+/// connection with an internal error.
+fn failing_server() -> &'static str {
+    mock_server("failing", |mut stream| async move {
+        let frame = read_frame(&mut stream).await;
+        let msg = Message::<IgnoredAny>::from_bytes(&mut &frame[..]).unwrap();
+        let error_headers = Headers::new(
+            msg.headers().stream_id(),
+            MessageType::ProtocolError,
+            MessageFlags::none(),
+        );
+        let payload = json!({ "message": "Unsupported operation" });
+        write_message(&mut stream, error_headers, Some(payload)).await;
+
+        let _ = read_frame(&mut stream).await;
+        let error_headers = Headers::new(0, MessageType::InternalError, MessageFlags::none());
+        write_message(&mut stream, error_headers, Some("Out of memory")).await;
+        let _ = read_frame(&mut stream).await;
+    })
+}
+
+/// A mock server that accepts the component update subscription and then ends it right away.
+fn ending_server() -> &'static str {
+    mock_server("ending", |mut stream| async move {
+        let frame = read_frame(&mut stream).await;
+        let msg = Message::<IgnoredAny>::from_bytes(&mut &frame[..]).unwrap();
+        let stream_id = msg.headers().stream_id();
+        let headers = Headers::new(stream_id, MessageType::Application, MessageFlags::none());
+        write_message(
+            &mut stream,
+            headers,
+            Some(ComponentUpdateSubscriptionResponse::new(None, None)),
+        )
+        .await;
+        let headers =
+            Headers::new(stream_id, MessageType::Application, MessageFlags::TerminateStream.into());
+        write_message(
+            &mut stream,
+            headers,
+            Some(ComponentUpdateSubscriptionResponse::new(None, None)),
+        )
+        .await;
+        let _ = read_frame(&mut stream).await;
+    })
+}
+
+/// A mock server that never answers the first request and reports the stream ID it was sent on
+/// along with the headers of the next message through `terminated`.
+fn silent_server(terminated: oneshot::Sender<(i32, Headers<'static>)>) -> &'static str {
+    mock_server("silent", |mut stream| async move {
+        let frame = read_frame(&mut stream).await;
+        let msg = Message::<IgnoredAny>::from_bytes(&mut &frame[..]).unwrap();
+        let stream_id = msg.headers().stream_id();
+        let frame = read_frame(&mut stream).await;
+        let msg = Message::<IgnoredAny>::from_bytes(&mut &frame[..]).unwrap();
+        terminated.send((stream_id, msg.headers().to_owned())).unwrap();
+        let _ = read_frame(&mut stream).await;
+    })
+}
+
+async fn handshake(stream: &mut UnixStream, auth_token: &str) {
+    let frame = read_frame(stream).await;
+    let msg: Message<ConnectRequest<'_>> = Message::from_bytes(&mut &frame[..]).unwrap();
+    assert_eq!(msg.headers().message_type(), MessageType::Connect);
+    assert_eq!(msg.headers().message_flags(), MessageFlags::none());
+    assert_eq!(msg.headers().stream_id(), 0);
+    assert_eq!(msg.payload(), ConnectRequest::new(auth_token).unwrap().payload());
+    let response_headers =
+        Headers::new(0, MessageType::ConnectAck, MessageFlags::ConnectionAccepted.into());
+    write_message(stream, response_headers, None::<ConnectResponse>).await;
+}
+
+async fn read_frame(stream: &mut UnixStream) -> Vec<u8> {
+    let mut frame = vec![0; SIZE];
+    stream.read_exact(&mut frame).await.unwrap();
+    let prelude = Prelude::from_bytes(&mut &frame[..]).unwrap();
+    frame.resize(prelude.total_len(), 0);
+    stream.read_exact(&mut frame[SIZE..]).await.unwrap();
+
+    frame
+}
+
+async fn write_message<Payload>(
+    stream: &mut UnixStream,
+    headers: Headers<'_>,
+    payload: Option<Payload>,
+) where
+    Payload: Serialize + std::fmt::Debug,
+{
+    let bytes = Message::new(headers, payload).to_bytes().unwrap();
+    stream.write_all(&bytes).await.unwrap();
 }
 
 #[test(tokio::test)]
 async fn test_ipc_client() {
-    let (sender, mut receiver) = channel(1);
-    mock_greengrass_server(sender);
+    let (sender, mut deferrals) = unbounded_channel();
+    let (terminations_sender, mut terminations) = unbounded_channel();
+    mock_greengrass_server(sender, terminations_sender);
+    let defer = RecheckAfterMs::Defer(60_000.try_into().unwrap());
 
-    let mut client = IpcClient::from_env().await.unwrap();
+    let client = IpcClient::from_env().await.unwrap();
+    // The server answers all pings, so the connection must stay alive throughout.
+    client.set_keep_alive(Some(KeepAlive::new(Duration::from_millis(20), 3)));
 
     client.update_state(LifecycleState::Running).await.unwrap();
 
+    // Concurrent calls from clones of the client, sharing the connection.
+    let calls: Vec<_> = (0..8)
+        .map(|_| {
+            let client = client.clone();
+            spawn(async move { client.update_state(LifecycleState::Running).await })
+        })
+        .collect();
+    for call in calls {
+        call.await.unwrap().unwrap();
+    }
+
     client.pause_component_update().await.unwrap();
-    receiver.recv().await.unwrap();
+    assert_eq!(deferrals.recv().await.unwrap(), defer);
+    // On resume, the deferral is released immediately.
     client.resume_component_update().await.unwrap();
+    assert_eq!(deferrals.recv().await.unwrap(), RecheckAfterMs::DontDefer);
+    // With nothing left to handle the updates for, the subscription is ended.
+    let subscription_stream_id = terminations.recv().await.unwrap();
+    assert!(subscription_stream_id > 0);
+
+    // Independent guards from a shared pauser.
+    let pauser = client.update_pauser();
+    assert!(!pauser.is_paused());
+    let guard1 = pauser.pause().await.unwrap();
+    let mut tracker = client.track_deployments().await.unwrap();
+    let guard2 = pauser.clone().pause().await.unwrap();
+    assert_eq!(deferrals.recv().await.unwrap(), defer);
+    drop(guard1);
+    assert!(pauser.is_paused());
+    drop(guard2);
+    assert!(!pauser.is_paused());
+    assert_eq!(deferrals.recv().await.unwrap(), RecheckAfterMs::DontDefer);
+
+    // The tracker may have subscribed after the deployment was announced but it can't miss the
+    // deployment being allowed to proceed.
+    let deployment_id = DEPLOYMENT_ID.parse::<Uuid>().unwrap();
+    loop {
+        let record = tracker.next().await.unwrap();
+        assert_eq!(record.deployment_id(), deployment_id);
+        match record.stage() {
+            DeploymentStage::Announced { is_ggc_restarting } => assert!(!is_ggc_restarting),
+            DeploymentStage::Deferred { count } => assert_eq!(count, 1),
+            DeploymentStage::Proceeded => break,
+            DeploymentStage::Completed => panic!("Deployment unexpectedly completed"),
+        }
+    }
+    drop(tracker);
+    assert!(terminations.recv().await.unwrap() > subscription_stream_id);
+
+    // Pre-update hooks run before the update is allowed to proceed, with the update deferred until
+    // they finish.
+    let hook_ran = Arc::new(AtomicBool::new(false));
+    let hook_ran_clone = hook_ran.clone();
+    let finish_hook = Arc::new(Notify::new());
+    let finish_hook_clone = finish_hook.clone();
+    client
+        .add_pre_update_hook(move |event| {
+            let hook_ran = hook_ran_clone.clone();
+            let finish_hook = finish_hook_clone.clone();
+            async move {
+                assert!(!event.is_ggc_restarting());
+                finish_hook.notified().await;
+                hook_ran.store(true, Ordering::SeqCst);
+            }
+        })
+        .await
+        .unwrap();
+    assert_eq!(deferrals.recv().await.unwrap(), RecheckAfterMs::Defer(30_000.try_into().unwrap()));
+    assert!(!hook_ran.load(Ordering::SeqCst));
+    finish_hook.notify_one();
+    assert_eq!(deferrals.recv().await.unwrap(), RecheckAfterMs::DontDefer);
+    assert!(hook_ran.load(Ordering::SeqCst));
+
+    // Generic operations on the low-level connection.
+    let conn = Connection::from_env().await.unwrap();
+    let _ = conn
+        .invoke::<UpdateState>(UpdateStateRequest::new_payload(LifecycleState::Running))
+        .await
+        .unwrap();
+
+    // Explicitly ending a subscription.
+    let subscription = conn.subscribe_to_component_updates().await.unwrap();
+    let stream_id = subscription.stream_id();
+    subscription.unsubscribe().await.unwrap();
+    assert_eq!(terminations.recv().await.unwrap(), stream_id);
+}
+
+#[test(tokio::test)]
+async fn test_dead_connection() {
+    let (pong_tx, pong_rx) = oneshot::channel();
+    let socket_path = unresponsive_server(pong_tx);
+
+    let client = IpcClient::new(socket_path, "unresponsive").await.unwrap();
+    // Pings from the server are answered even without keep-alive set up.
+    pong_rx.await.unwrap();
+
+    client.set_keep_alive(Some(KeepAlive::new(Duration::from_millis(10), 2)));
+    let res = client.update_state(LifecycleState::Running).await;
+    assert!(matches!(res, Err(Error::ConnectionDead)), "{res:?}");
+    let res = client.update_state(LifecycleState::Running).await;
+    assert!(matches!(res, Err(Error::ConnectionDead)), "{res:?}");
+}
+
+#[test(tokio::test)]
+async fn test_server_errors() {
+    let socket_path = failing_server();
+    let client = IpcClient::new(socket_path, "failing").await.unwrap();
+
+    // Errors on the stream of a call fail just that call.
+    let res = client.update_state(LifecycleState::Running).await;
+    assert!(matches!(&res, Err(Error::Protocol(e)) if e == "Unsupported operation"), "{res:?}");
+
+    // Errors on stream 0 fail the pending call and all calls after it.
+    for _ in 0..2 {
+        let res = client.update_state(LifecycleState::Running).await;
+        assert!(matches!(&res, Err(Error::InternalServer(e)) if e == "Out of memory"), "{res:?}");
+    }
+}
+
+#[test(tokio::test)]
+async fn test_subscription_ended() {
+    let socket_path = ending_server();
+    let client = IpcClient::new(socket_path, "ending").await.unwrap();
+
+    // The tracker ends along with the handling of the component updates.
+    let mut tracker = client.track_deployments().await.unwrap();
+    assert_eq!(tracker.next().await, None);
+}
+
+#[test(tokio::test)]
+async fn test_cancelled_subscription() {
+    let (terminated_tx, terminated_rx) = oneshot::channel();
+    let socket_path = silent_server(terminated_tx);
+    let conn = Connection::new(socket_path, "silent").await.unwrap();
+
+    // The request is sent but the subscription is given up on before the response arrives.
+    let res =
+        tokio::time::timeout(Duration::from_millis(50), conn.subscribe_to_component_updates())
+            .await;
+    assert!(res.is_err());
+    let terminated = tokio::time::timeout(Duration::from_secs(5), terminated_rx);
+    let (stream_id, headers) = terminated.await.unwrap().unwrap();
+    assert_eq!(headers.stream_id(), stream_id);
+    assert_eq!(headers.message_flags(), MessageFlags::TerminateStream);
+}
+
+#[test(tokio::test)]
+async fn test_oversized_message() {
+    let socket_path = oversized_server();
+    let client = IpcClient::new(socket_path, "oversized").await.unwrap();
+
+    let res = client.update_state(LifecycleState::Running).await;
+    assert!(matches!(res, Err(Error::FrameTooLarge { .. })), "{res:?}");
+    // The oversized message is skipped and the connection stays usable.
+    client.update_state(LifecycleState::Running).await.unwrap();
 }
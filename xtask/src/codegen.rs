@@ -0,0 +1,506 @@
+//! Rust code generation from the Greengrass IPC Smithy JSON model.
+//!
+//! Supported shapes are structures, unions, enums (both `enum` shapes and `string` shapes with the
+//! `smithy.api#enum` trait), lists, sets, maps, simple types and operations. An operation is a
+//! streaming one if a member of its output targets a shape with the `smithy.api#streaming` trait,
+//! which is then the type of its events. That member is not part of the response payload, so it's
+//! left out of the response structure.
+//!
+//! Only the shapes used by the generated operations are generated, along with every error
+//! structure.
+
+use std::{collections::BTreeSet, fmt::Write};
+
+use serde_json::{Map, Value};
+
+const NAMESPACE: &str = "aws.greengrass#";
+
+/// Operations that are implemented by hand in the crate and so are not generated.
+const HAND_WRITTEN: &[&str] =
+    &["UpdateState", "DeferComponentUpdate", "SubscribeToComponentUpdates"];
+
+/// The errors that have a dedicated `ErrorCode` variant. Generation fails for any other error,
+/// which needs a variant first.
+const KNOWN_ERRORS: &[&str] = &[
+    "ServiceError",
+    "ResourceNotFoundError",
+    "UnauthorizedError",
+    "InvalidArgumentsError",
+    "ConflictError",
+    "FailedUpdateConditionCheckError",
+    "ComponentNotFoundError",
+    "InvalidTokenError",
+    "InvalidArtifactsDirectoryPathError",
+    "InvalidRecipeDirectoryPathError",
+    "InvalidCredentialError",
+    "InvalidClientDeviceAuthTokenError",
+];
+
+const PREAMBLE: &str = "\
+//! Types and operations generated from the Greengrass IPC Smithy model.
+//!
+//! Generated by `cargo xtask codegen`, do not edit by hand.
+#![allow(unused_imports, clippy::large_enum_variant, clippy::upper_case_acronyms)]
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ErrorCode, ModeledError, Operation, StreamingOperation};
+use crate::{Connection, Result, Subscription};
+";
+
+/// Generate the Rust code for all `aws.greengrass` shapes of the model.
+pub(crate) fn generate(model: &Value) -> Result<String, String> {
+    let shapes = model.get("shapes").and_then(Value::as_object).ok_or("Model has no shapes")?;
+    let generator = Generator { shapes };
+    let ids = generator.reachable()?;
+
+    let mut types = String::new();
+    let mut operations = String::new();
+    let mut methods = String::new();
+    for id in ids {
+        let shape = &shapes[id];
+        let name = local_name(id);
+        match shape_type(shape) {
+            "structure" if has_trait(shape, "smithy.api#error") => {
+                if !KNOWN_ERRORS.contains(&name) {
+                    return Err(format!("Error `{name}` has no `ErrorCode` variant"));
+                }
+                generator.structure(&mut types, name, shape, "error")?;
+                writeln!(types, "impl ModeledError for {name} {{").unwrap();
+                writeln!(types, "    const CODE: ErrorCode = ErrorCode::{name};").unwrap();
+                writeln!(types, "}}\n").unwrap();
+            }
+            "structure" => generator.structure(&mut types, name, shape, "structure")?,
+            "union" => generator.union(&mut types, name, shape)?,
+            "enum" => generator.enumeration(&mut types, name, shape, enum_shape_values(shape))?,
+            "string" if has_trait(shape, "smithy.api#enum") => {
+                generator.enumeration(&mut types, name, shape, enum_trait_values(shape))?
+            }
+            "operation" if !HAND_WRITTEN.contains(&name) => {
+                generator.operation(&mut operations, &mut methods, name, shape)?
+            }
+            _ => (),
+        }
+    }
+
+    let mut code = PREAMBLE.to_owned();
+    code.push_str(&types);
+    code.push_str(&operations);
+    if !methods.is_empty() {
+        write!(code, "\nimpl Connection {{{methods}}}\n").unwrap();
+    }
+
+    Ok(code)
+}
+
+struct Generator<'m> {
+    shapes: &'m Map<String, Value>,
+}
+
+impl Generator<'_> {
+    /// The IDs of the shapes to generate, sorted: the operations that aren't hand-written, the
+    /// shapes they use and all error structures.
+    fn reachable(&self) -> Result<BTreeSet<&str>, String> {
+        let mut pending = Vec::new();
+        for (id, shape) in self.shapes.iter().filter(|(id, _)| id.starts_with(NAMESPACE)) {
+            match shape_type(shape) {
+                "operation" if !HAND_WRITTEN.contains(&local_name(id)) => {
+                    pending.push(id.as_str());
+                    for key in ["input", "output"] {
+                        pending.extend(shape.get(key).map(target).transpose()?);
+                    }
+                    for error in shape.get("errors").and_then(Value::as_array).into_iter().flatten()
+                    {
+                        pending.push(target(error)?);
+                    }
+                }
+                "structure" if has_trait(shape, "smithy.api#error") => pending.push(id.as_str()),
+                _ => (),
+            }
+        }
+
+        let mut reachable = BTreeSet::new();
+        while let Some(id) = pending.pop() {
+            if !id.starts_with(NAMESPACE) || !reachable.insert(id) {
+                continue;
+            }
+            let shape = self.shapes.get(id).ok_or(format!("Unknown shape `{id}`"))?;
+            if shape_type(shape) == "operation" {
+                continue;
+            }
+            for (_, member) in members(shape) {
+                pending.push(target(member)?);
+            }
+            for key in ["member", "key", "value"] {
+                pending.extend(shape.get(key).map(target).transpose()?);
+            }
+        }
+
+        Ok(reachable)
+    }
+
+    fn is_streaming(&self, id: &str) -> bool {
+        self.shapes.get(id).is_some_and(|shape| has_trait(shape, "smithy.api#streaming"))
+    }
+
+    fn structure(
+        &self,
+        out: &mut String,
+        name: &str,
+        shape: &Value,
+        kind: &str,
+    ) -> Result<(), String> {
+        write_docs(out, shape, "", &format!("The `{name}` {kind}."));
+        writeln!(out, "#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]").unwrap();
+        writeln!(out, "pub struct {name} {{").unwrap();
+        for (member_name, member) in members(shape) {
+            if self.is_streaming(target(member)?) {
+                continue;
+            }
+            let ty = self.rust_type(target(member)?)?;
+            let field = field_name(member_name);
+            let json_name = json_name(member_name, member);
+            write_docs(out, member, "    ", &format!("The `{member_name}` member."));
+            if has_trait(member, "smithy.api#required") {
+                writeln!(out, "    #[serde(rename = \"{json_name}\")]").unwrap();
+                writeln!(out, "    pub {field}: {ty},").unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "    #[serde(rename = \"{json_name}\", default, \
+                     skip_serializing_if = \"Option::is_none\")]"
+                )
+                .unwrap();
+                writeln!(out, "    pub {field}: Option<{ty}>,").unwrap();
+            }
+        }
+        writeln!(out, "}}\n").unwrap();
+
+        Ok(())
+    }
+
+    fn union(&self, out: &mut String, name: &str, shape: &Value) -> Result<(), String> {
+        write_docs(out, shape, "", &format!("The `{name}` union."));
+        writeln!(out, "#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]").unwrap();
+        writeln!(out, "pub enum {name} {{").unwrap();
+        for (member_name, member) in members(shape) {
+            let ty = self.rust_type(target(member)?)?;
+            write_docs(out, member, "    ", &format!("The `{member_name}` member."));
+            writeln!(out, "    #[serde(rename = \"{}\")]", json_name(member_name, member)).unwrap();
+            writeln!(out, "    {}({ty}),", variant_name(member_name)).unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+
+        Ok(())
+    }
+
+    fn enumeration(
+        &self,
+        out: &mut String,
+        name: &str,
+        shape: &Value,
+        values: Vec<(String, String)>,
+    ) -> Result<(), String> {
+        if values.is_empty() {
+            return Err(format!("Enum `{name}` has no values"));
+        }
+
+        write_docs(out, shape, "", &format!("The `{name}` enum."));
+        writeln!(out, "#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]")
+            .unwrap();
+        writeln!(out, "pub enum {name} {{").unwrap();
+        for (variant, value) in values {
+            writeln!(out, "    /// `{value}`.").unwrap();
+            writeln!(out, "    #[serde(rename = \"{value}\")]").unwrap();
+            writeln!(out, "    {},", variant_name(&variant)).unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+
+        Ok(())
+    }
+
+    fn operation(
+        &self,
+        out: &mut String,
+        methods: &mut String,
+        name: &str,
+        shape: &Value,
+    ) -> Result<(), String> {
+        let input = target(shape.get("input").ok_or(format!("`{name}` has no input"))?)?;
+        let output = target(shape.get("output").ok_or(format!("`{name}` has no output"))?)?;
+        let request = self.rust_type(input)?;
+        let response = self.rust_type(output)?;
+        let errors = shape
+            .get("errors")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|error| target(error).map(local_name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let event = match self.shapes.get(output) {
+            Some(output_shape) => members(output_shape)
+                .map(|(_, member)| target(member))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .find(|t| self.is_streaming(t))
+                .map(|t| self.rust_type(t))
+                .transpose()?,
+            None => None,
+        };
+
+        write_docs(out, shape, "", &format!("The `{name}` operation."));
+        writeln!(out, "#[derive(Debug)]").unwrap();
+        writeln!(out, "pub struct {name};\n").unwrap();
+        writeln!(out, "impl Operation for {name} {{").unwrap();
+        writeln!(out, "    const NAME: &'static str = \"{NAMESPACE}{name}\";").unwrap();
+        writeln!(out, "    const REQUEST_MODEL_TYPE: &'static str = \"{input}\";").unwrap();
+        writeln!(out, "    const ERRORS: &'static [ErrorCode] = &[").unwrap();
+        for error in errors {
+            writeln!(out, "        ErrorCode::{error},").unwrap();
+        }
+        writeln!(out, "    ];\n").unwrap();
+        writeln!(out, "    type Request<'r> = {request};").unwrap();
+        writeln!(out, "    type Response = {response};").unwrap();
+        writeln!(out, "}}\n").unwrap();
+
+        let method = snake_case(name);
+        match event {
+            Some(event) => {
+                writeln!(out, "impl StreamingOperation for {name} {{").unwrap();
+                writeln!(out, "    type Event = {event};").unwrap();
+                writeln!(out, "}}\n").unwrap();
+
+                write!(
+                    methods,
+                    "
+    /// Subscribes to the events of the [`{name}`] operation.
+    pub async fn {method}(&self, request: {request}) -> Result<Subscription<{event}>> {{
+        self.subscribe::<{name}>(request).await
+    }}
+"
+                )
+                .unwrap();
+            }
+            None => write!(
+                methods,
+                "
+    /// Invokes the [`{name}`] operation.
+    pub async fn {method}(&self, request: {request}) -> Result<{response}> {{
+        self.invoke::<{name}>(request).await
+    }}
+"
+            )
+            .unwrap(),
+        }
+
+        Ok(())
+    }
+
+    /// The Rust type for the shape with the given ID.
+    fn rust_type(&self, id: &str) -> Result<String, String> {
+        let prelude = match id {
+            "smithy.api#String" => Some("String"),
+            "smithy.api#Boolean" | "smithy.api#PrimitiveBoolean" => Some("bool"),
+            "smithy.api#Byte" | "smithy.api#PrimitiveByte" => Some("i8"),
+            "smithy.api#Short" | "smithy.api#PrimitiveShort" => Some("i16"),
+            "smithy.api#Integer" | "smithy.api#PrimitiveInteger" => Some("i32"),
+            "smithy.api#Long" | "smithy.api#PrimitiveLong" => Some("i64"),
+            "smithy.api#Float" | "smithy.api#PrimitiveFloat" => Some("f32"),
+            "smithy.api#Double" | "smithy.api#PrimitiveDouble" => Some("f64"),
+            // Sent as epoch seconds.
+            "smithy.api#Timestamp" => Some("f64"),
+            // Sent as base64.
+            "smithy.api#Blob" => Some("String"),
+            "smithy.api#Document" => Some("serde_json::Value"),
+            _ => None,
+        };
+        if let Some(ty) = prelude {
+            return Ok(ty.into());
+        }
+
+        let shape = self.shapes.get(id).ok_or(format!("Unknown shape `{id}`"))?;
+        let ty = match shape_type(shape) {
+            "structure" | "union" | "enum" => local_name(id).into(),
+            "string" if has_trait(shape, "smithy.api#enum") => local_name(id).into(),
+            "list" | "set" => {
+                let member = shape.get("member").ok_or(format!("List `{id}` has no member"))?;
+                format!("Vec<{}>", self.rust_type(target(member)?)?)
+            }
+            "map" => {
+                let value = shape.get("value").ok_or(format!("Map `{id}` has no value"))?;
+                format!("HashMap<String, {}>", self.rust_type(target(value)?)?)
+            }
+            simple => {
+                let mut chars = simple.chars();
+                let capitalized = chars
+                    .next()
+                    .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default();
+                return self
+                    .rust_type(&format!("smithy.api#{capitalized}"))
+                    .map_err(|_| format!("Unsupported type `{simple}` of shape `{id}`"));
+            }
+        };
+
+        Ok(ty)
+    }
+}
+
+fn shape_type(shape: &Value) -> &str {
+    shape.get("type").and_then(Value::as_str).unwrap_or_default()
+}
+
+fn has_trait(shape: &Value, name: &str) -> bool {
+    shape.get("traits").and_then(|traits| traits.get(name)).is_some()
+}
+
+fn members(shape: &Value) -> impl Iterator<Item = (&String, &Value)> {
+    shape.get("members").and_then(Value::as_object).into_iter().flatten()
+}
+
+fn target(reference: &Value) -> Result<&str, String> {
+    reference.get("target").and_then(Value::as_str).ok_or(format!("No target in {reference}"))
+}
+
+fn local_name(id: &str) -> &str {
+    id.rsplit('#').next().unwrap_or(id)
+}
+
+fn json_name<'a>(member_name: &'a str, member: &'a Value) -> &'a str {
+    member
+        .get("traits")
+        .and_then(|traits| traits.get("smithy.api#jsonName"))
+        .and_then(Value::as_str)
+        .unwrap_or(member_name)
+}
+
+/// The `(variant, value)` pairs of an `enum` shape.
+fn enum_shape_values(shape: &Value) -> Vec<(String, String)> {
+    members(shape)
+        .map(|(name, member)| {
+            let value = member
+                .get("traits")
+                .and_then(|traits| traits.get("smithy.api#enumValue"))
+                .and_then(Value::as_str)
+                .unwrap_or(name);
+            (name.clone(), value.to_owned())
+        })
+        .collect()
+}
+
+/// The `(variant, value)` pairs of a `string` shape with the `smithy.api#enum` trait.
+fn enum_trait_values(shape: &Value) -> Vec<(String, String)> {
+    shape["traits"]["smithy.api#enum"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|definition| {
+            let value = definition.get("value").and_then(Value::as_str)?;
+            let name = definition.get("name").and_then(Value::as_str).unwrap_or(value);
+            Some((name.to_owned(), value.to_owned()))
+        })
+        .collect()
+}
+
+/// Write the first paragraph of the shape's documentation, or the fallback if it has none.
+fn write_docs(out: &mut String, shape: &Value, indent: &str, fallback: &str) {
+    let docs = shape
+        .get("traits")
+        .and_then(|traits| traits.get("smithy.api#documentation"))
+        .and_then(Value::as_str)
+        .map(strip_tags)
+        .and_then(|docs| {
+            docs.split("\n\n").map(str::trim).find(|p| !p.is_empty()).map(str::to_owned)
+        })
+        .unwrap_or_else(|| fallback.to_owned());
+    for line in docs.lines() {
+        writeln!(out, "{indent}/// {}", line.trim()).unwrap();
+    }
+}
+
+fn strip_tags(docs: &str) -> String {
+    let mut stripped = String::with_capacity(docs.len());
+    let mut in_tag = false;
+    for c in docs.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => stripped.push(c),
+            _ => (),
+        }
+    }
+
+    stripped
+}
+
+fn snake_case(name: &str) -> String {
+    // `IoT` is one word, not `io_t`.
+    let chars: Vec<_> = name.replace("IoT", "Iot").chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(char::is_ascii_lowercase);
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+
+    snake
+}
+
+fn field_name(member_name: &str) -> String {
+    let name = snake_case(member_name);
+    match name.as_str() {
+        "type" | "match" | "ref" | "self" | "mod" | "move" | "use" | "where" | "loop" | "fn"
+        | "impl" | "in" | "as" | "crate" | "enum" | "struct" | "trait" | "async" | "await"
+        | "dyn" | "static" | "const" => format!("r#{name}"),
+        _ => name,
+    }
+}
+
+fn variant_name(name: &str) -> String {
+    let variant: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().unwrap_or_default().to_ascii_uppercase();
+            // Keep camel case as is, but turn `SCREAMING_CASE` into `Screaming` + `Case`.
+            let rest: String = if part.chars().any(|c| c.is_ascii_lowercase()) {
+                chars.collect()
+            } else {
+                chars.map(|c| c.to_ascii_lowercase()).collect()
+            };
+            format!("{first}{rest}")
+        })
+        .collect();
+
+    match variant.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("V{variant}"),
+        _ => variant,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{snake_case, variant_name};
+
+    #[test]
+    fn names() {
+        assert_eq!(snake_case("PublishToIoTCore"), "publish_to_iot_core");
+        assert_eq!(snake_case("GetSecretValue"), "get_secret_value");
+        assert_eq!(snake_case("deploymentId"), "deployment_id");
+        assert_eq!(variant_name("RUNNING"), "Running");
+        assert_eq!(variant_name("AT_LEAST_ONCE"), "AtLeastOnce");
+        assert_eq!(variant_name("jsonMessage"), "JsonMessage");
+        assert_eq!(variant_name("0"), "V0");
+    }
+}
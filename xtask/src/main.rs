@@ -0,0 +1,46 @@
+//! Development tasks for the `greengrass-sdk` crate, run through `cargo xtask <TASK>`.
+//!
+//! Tasks:
+//!
+//! * `codegen [MODEL [OUTPUT]]`: Generate the request, response, event and error types of every
+//!   operation in the `aws.greengrass` Smithy JSON model, along with their [`Operation`]
+//!   implementations and the matching `Connection` methods. The model defaults to the one checked
+//!   in at `xtask/model/greengrass-ipc.json` and the output to `src/protocol/generated.rs`.
+//!
+//! [`Operation`]: ../greengrass_sdk/protocol/trait.Operation.html
+
+use std::{env, fs, process::ExitCode};
+
+mod codegen;
+
+const DEFAULT_MODEL: &str = "xtask/model/greengrass-ipc.json";
+const DEFAULT_OUTPUT: &str = "src/protocol/generated.rs";
+
+fn main() -> ExitCode {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let res = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["codegen"] => run_codegen(DEFAULT_MODEL, DEFAULT_OUTPUT),
+        ["codegen", model] => run_codegen(model, DEFAULT_OUTPUT),
+        ["codegen", model, output] => run_codegen(model, output),
+        _ => Err("Usage: cargo xtask codegen [MODEL [OUTPUT]]".into()),
+    };
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_codegen(model: &str, output: &str) -> Result<(), String> {
+    let model = fs::read(model).map_err(|e| format!("Failed to read `{model}`: {e}"))?;
+    let model = serde_json::from_slice(&model).map_err(|e| format!("Invalid model: {e}"))?;
+    let code = codegen::generate(&model)?;
+    fs::write(output, code).map_err(|e| format!("Failed to write `{output}`: {e}"))?;
+    println!("Generated `{output}`, run `cargo fmt` to format it.");
+
+    Ok(())
+}